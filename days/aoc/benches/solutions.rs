@@ -0,0 +1,33 @@
+//! Benchmark every registered solution, on the cached `SESSION` input where
+//! there is one and a generated input otherwise.
+use criterion::{Criterion, criterion_group, criterion_main};
+use libaoc::registry;
+
+include!(concat!(env!("OUT_DIR"), "/days.rs"));
+
+/// Size of the generated inputs used when no input is cached.
+const SIZE: usize = 100;
+
+fn bench(c: &mut Criterion) {
+    let registry = registry::collect().unwrap_or_else(|e| panic!("{e}"));
+    for (puzzle, day) in &registry {
+        let input = match (
+            libaoc::cached_input(None, puzzle.year, puzzle.day),
+            day.generator,
+        ) {
+            (Some(input), _) => libaoc::normalize(&input),
+            (None, Some(generate)) => generate(0, SIZE),
+            (None, None) => continue,
+        };
+        let mut group = c.benchmark_group(format!("{}/{:02}", puzzle.year, puzzle.day));
+        for (part, solver) in (1..).zip(day.parts) {
+            if let Some(solver) = solver {
+                group.bench_function(format!("part_{part}"), |b| b.iter(|| solver(&input)));
+            }
+        }
+        group.finish();
+    }
+}
+
+criterion_group!(benches, bench);
+criterion_main!(benches);
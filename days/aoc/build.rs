@@ -0,0 +1,31 @@
+//! Link in every day crate listed in `[dependencies]`, so their `#[aoc]`
+//! functions reach the registry without another list to maintain. Tests also
+//! get each day crate's manifest, to check that it registered.
+use std::fmt::Write;
+
+fn main() {
+    println!("cargo::rerun-if-changed=Cargo.toml");
+    let manifest = std::fs::read_to_string("Cargo.toml").expect("read Cargo.toml");
+    let manifest: toml::Table = manifest.parse().expect("parse Cargo.toml");
+    let dependencies = manifest["dependencies"]
+        .as_table()
+        .expect("[dependencies] table");
+    let mut source = String::new();
+    let mut manifests = String::new();
+    for (name, dependency) in dependencies {
+        if name.split(['-', '_']).any(|part| part.starts_with("day")) {
+            writeln!(source, "use {} as _;", name.replace('-', "_")).unwrap();
+            if let Some(path) = dependency.get("path").and_then(|path| path.as_str()) {
+                writeln!(
+                    manifests,
+                    "    include_str!(concat!(env!(\"CARGO_MANIFEST_DIR\"), \"/{path}/Cargo.toml\")),"
+                )
+                .unwrap();
+            }
+        }
+    }
+    let out = std::env::var("OUT_DIR").expect("OUT_DIR");
+    std::fs::write(format!("{out}/days.rs"), source).expect("write days.rs");
+    let manifests = format!("const MANIFESTS: &[&str] = &[\n{manifests}];\n");
+    std::fs::write(format!("{out}/manifests.rs"), manifests).expect("write manifests.rs");
+}
@@ -0,0 +1,214 @@
+//! `aoc check-all`: run every solved day on every available input and compare
+//! with the recorded answers, since input-specific assumptions often only break
+//! on someone else's input.
+use std::fmt;
+use std::panic::{self, AssertUnwindSafe};
+
+use libaoc::answers::{self, AnswerBook};
+
+use crate::days::{self, Solver};
+
+/// One input to check a day against.
+pub struct Input {
+    /// Where its answers are recorded in the [`AnswerBook`].
+    pub key: String,
+    pub label: String,
+    pub text: String,
+}
+
+/// The cached input of each profile (`None` being the `SESSION` profile), then
+/// `seeds` generated inputs of `size`.
+pub fn inputs(
+    year: u16,
+    day: u8,
+    profiles: &[Option<String>],
+    seeds: u64,
+    size: usize,
+) -> Vec<Input> {
+    let cached = profiles.iter().filter_map(|profile| {
+        let profile = profile.as_deref();
+        let text = libaoc::cached_input(profile, year, day)?;
+        Some(Input {
+            key: answers::profile_key(profile, year, day),
+            label: profile.unwrap_or("session").to_owned(),
+            text: libaoc::normalize(&text),
+        })
+    });
+    let generated = days::generator(year, day)
+        .into_iter()
+        .flat_map(move |generate| {
+            (0..seeds).map(move |seed| Input {
+                key: answers::generated_key(year, day, seed, size),
+                label: format!("generated {seed}"),
+                text: generate(seed, size),
+            })
+        });
+    cached.chain(generated).collect()
+}
+
+/// How one part fared on one input.
+#[derive(Debug, PartialEq, Eq)]
+pub enum Outcome {
+    Match,
+    Mismatch {
+        expected: usize,
+        actual: usize,
+    },
+    /// No answer was recorded; `recorded` if this run recorded it.
+    Unrecorded {
+        actual: usize,
+        recorded: bool,
+    },
+    Failed(String),
+}
+
+impl Outcome {
+    pub fn new(expected: Option<usize>, result: Result<usize, String>) -> Self {
+        match (expected, result) {
+            (_, Err(e)) => Self::Failed(e),
+            (Some(expected), Ok(actual)) if expected == actual => Self::Match,
+            (Some(expected), Ok(actual)) => Self::Mismatch { expected, actual },
+            (None, Ok(actual)) => Self::Unrecorded {
+                actual,
+                recorded: false,
+            },
+        }
+    }
+
+    pub fn is_failure(&self) -> bool {
+        matches!(self, Self::Mismatch { .. } | Self::Failed(_))
+    }
+}
+
+impl fmt::Display for Outcome {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::Match => write!(f, "ok"),
+            Self::Mismatch { expected, actual } => {
+                write!(f, "MISMATCH expected {expected}, got {actual}")
+            }
+            Self::Unrecorded {
+                actual,
+                recorded: true,
+            } => write!(f, "{actual} (recorded)"),
+            Self::Unrecorded { actual, .. } => write!(f, "{actual} (unrecorded)"),
+            Self::Failed(e) => write!(f, "FAILED {e}"),
+        }
+    }
+}
+
+/// Run `solver`, turning a panic into an error rather than aborting the whole
+/// check. Only works where panics unwind, i.e. not under the release profile.
+pub fn run(solver: Solver, input: &str) -> Result<usize, String> {
+    let hook = panic::take_hook();
+    panic::set_hook(Box::new(|_| {}));
+    let result = panic::catch_unwind(AssertUnwindSafe(|| solver(input)));
+    panic::set_hook(hook);
+    match result {
+        Ok(answer) => answer.map_err(|e| e.to_string()),
+        Err(payload) => {
+            let message = payload
+                .downcast_ref::<&str>()
+                .map(|s| (*s).to_owned())
+                .or_else(|| payload.downcast_ref::<String>().cloned())
+                .unwrap_or_else(|| "unknown panic".into());
+            Err(format!("panicked: {message}"))
+        }
+    }
+}
+
+/// Check both parts of `day` on `input`, recording new answers if `record`.
+pub fn check(
+    year: u16,
+    day: u8,
+    input: &Input,
+    book: &mut AnswerBook,
+    record: bool,
+) -> [Outcome; 2] {
+    let solvers = days::solvers(year, day).expect("only solved days are checked");
+    let recorded = book.get(&input.key);
+    [1, 2].map(|part| {
+        let result = run(solvers[usize::from(part - 1)], &input.text);
+        let mut outcome = Outcome::new(recorded.part(part), result);
+        if let Outcome::Unrecorded { actual, recorded } = &mut outcome
+            && record
+        {
+            book.record(&input.key, part, *actual);
+            *recorded = true;
+        }
+        outcome
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use libaoc::{InputError, aoc};
+
+    #[test]
+    fn outcomes() {
+        assert_eq!(Outcome::new(Some(3), Ok(3)), Outcome::Match);
+        let mismatch = Outcome::new(Some(3), Ok(4));
+        assert!(mismatch.is_failure());
+        assert_eq!(mismatch.to_string(), "MISMATCH expected 3, got 4");
+        let unrecorded = Outcome::new(None, Ok(4));
+        assert!(!unrecorded.is_failure());
+        assert_eq!(unrecorded.to_string(), "4 (unrecorded)");
+        assert!(Outcome::new(None, Err("line 1: bad".into())).is_failure());
+    }
+
+    #[test]
+    fn panics_are_failures() {
+        fn boom(_: &str) -> Result<usize, InputError> {
+            panic!("assumed uniform widths")
+        }
+        fn fine(input: &str) -> Result<usize, InputError> {
+            Ok(input.len())
+        }
+        assert_eq!(
+            run(boom, ""),
+            Err("panicked: assumed uniform widths".into())
+        );
+        assert_eq!(run(fine, "abc"), Ok(3));
+    }
+
+    #[aoc(year = 2015, day = 25, part = 1)]
+    fn solved_1(input: &str) -> Result<usize, InputError> {
+        Ok(input.len())
+    }
+
+    #[aoc(year = 2015, day = 25, part = 2)]
+    fn solved_2(input: &str) -> Result<usize, InputError> {
+        Ok(input.len())
+    }
+
+    #[aoc(year = 2015, day = 25, generator)]
+    fn unwritten(_seed: u64, _size: usize) -> String {
+        todo!()
+    }
+
+    #[test]
+    fn todo_generators_are_not_run() {
+        assert!(days::solvers(2015, 25).is_some() && days::generator(2015, 25).is_none());
+        assert!(inputs(2015, 25, &[], 3, 10).is_empty());
+    }
+
+    #[test]
+    fn records_new_answers() {
+        let mut book = AnswerBook::default();
+        let input = Input {
+            key: "test/2025/1".into(),
+            label: "test".into(),
+            text: "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n".into(),
+        };
+        let [one, two] = check(2025, 1, &input, &mut book, true);
+        assert_eq!(one.to_string(), "3 (recorded)");
+        assert_eq!(two.to_string(), "6 (recorded)");
+        assert_eq!(
+            check(2025, 1, &input, &mut book, false),
+            [Outcome::Match, Outcome::Match]
+        );
+        book.record("test/2025/1", 2, 7);
+        assert!(check(2025, 1, &input, &mut book, false)[1].is_failure());
+    }
+}
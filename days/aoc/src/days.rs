@@ -0,0 +1,72 @@
+//! The days linked into the runner, as registered with `#[aoc]`. A day is
+//! added by adding its crate to `[dependencies]`.
+use std::sync::LazyLock;
+
+use libaoc::grammar::Grammar;
+use libaoc::manifest::Puzzle;
+use libaoc::registry::{self, Day, Registry};
+pub use libaoc::registry::{Generator, Solver};
+
+include!(concat!(env!("OUT_DIR"), "/days.rs"));
+
+/// Every registered day.
+///
+/// # Panics
+/// If the registrations conflict, which is a bug in a day crate.
+pub fn registry() -> &'static Registry {
+    static REGISTRY: LazyLock<Registry> =
+        LazyLock::new(|| registry::collect().unwrap_or_else(|e| panic!("{e}")));
+    &REGISTRY
+}
+
+fn day(year: u16, day: u8) -> Option<&'static Day> {
+    registry().get(&Puzzle { year, day })
+}
+
+/// The input generator for `day` of `year`, if one is registered.
+pub fn generator(year: u16, day: u8) -> Option<Generator> {
+    self::day(year, day)?.generator
+}
+
+/// The input grammar for `day` of `year`, if one is registered.
+pub fn grammar(year: u16, day: u8) -> Option<Grammar> {
+    self::day(year, day)?.grammar.map(|grammar| grammar())
+}
+
+/// The solvers for both parts of `day` of `year`, if both are registered.
+pub fn solvers(year: u16, day: u8) -> Option<[Solver; 2]> {
+    self::day(year, day)?.solvers()
+}
+
+/// Every registered day of `year`, in order.
+pub fn year(year: u16) -> impl Iterator<Item = (u8, &'static Day)> {
+    registry()
+        .range(Puzzle { year, day: 1 }..=Puzzle { year, day: 25 })
+        .map(|(puzzle, day)| (puzzle.day, day))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    include!(concat!(env!("OUT_DIR"), "/manifests.rs"));
+
+    #[test]
+    fn every_dependency_is_registered() {
+        assert!(!MANIFESTS.is_empty());
+        for manifest in MANIFESTS {
+            let puzzle = libaoc::manifest::puzzle(manifest).unwrap();
+            assert!(
+                registry().contains_key(&puzzle),
+                "{puzzle} is not registered"
+            );
+        }
+    }
+
+    #[test]
+    fn lists_a_year() {
+        let days: Vec<_> = year(2024).map(|(day, _)| day).collect();
+        assert_eq!(days, [1]);
+        assert!(year(2014).next().is_none());
+    }
+}
@@ -0,0 +1,96 @@
+use std::fmt::Write;
+
+use libaoc::leaderboard::{Leaderboard, Member, format_duration};
+
+/// The standings, then for each day (or just `day`) every member's star times
+/// and the gap between their two stars.
+pub fn render(board: &Leaderboard, year: u16, day: Option<u8>) -> String {
+    let standings = board.standings();
+    let width = standings
+        .iter()
+        .map(|m| m.display_name().len())
+        .max()
+        .unwrap_or(0);
+    let mut out = String::new();
+    for (rank, member) in standings.iter().enumerate() {
+        writeln!(
+            out,
+            "{:>3}. {:<width$}  {:>5} pts  {:>2}*",
+            rank + 1,
+            member.display_name(),
+            member.local_score,
+            member.stars
+        )
+        .unwrap();
+    }
+    for day in day.map_or(1..=25, |day| day..=day) {
+        let mut solvers: Vec<_> = standings
+            .iter()
+            .filter(|m| m.star_time(year, day, 1).is_some())
+            .collect();
+        if solvers.is_empty() {
+            continue;
+        }
+        solvers.sort_by_key(|m| {
+            (
+                m.star_time(year, day, 2).unwrap_or(u64::MAX),
+                m.star_time(year, day, 1),
+            )
+        });
+        writeln!(out, "\nDay {day}").unwrap();
+        for member in solvers {
+            writeln!(
+                out,
+                "  {:<width$}  {}",
+                member.display_name(),
+                times(member, year, day)
+            )
+            .unwrap();
+        }
+    }
+    out
+}
+
+fn times(member: &Member, year: u16, day: u8) -> String {
+    let time = |part| {
+        member
+            .star_time(year, day, part)
+            .map_or("-".into(), format_duration)
+    };
+    let delta = member
+        .delta(day)
+        .map_or(String::new(), |d| format!("  (+{})", format_duration(d)));
+    format!("part 1 {:>9}  part 2 {:>9}{delta}", time(1), time(2))
+        .trim_end()
+        .to_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn renders_standings_and_days() {
+        let board: Leaderboard = serde_json::from_str(
+            r#"{"event": "2025", "owner_id": 1, "members": {
+                "1": {"id": 1, "name": "alex", "stars": 2, "local_score": 4, "last_star_ts": 1764566100,
+                      "completion_day_level": {"1": {
+                          "1": {"get_star_ts": 1764565500, "star_index": 1},
+                          "2": {"get_star_ts": 1764566100, "star_index": 3}}}},
+                "2": {"id": 2, "name": null, "stars": 1, "local_score": 1, "last_star_ts": 1764565800,
+                      "completion_day_level": {"1": {
+                          "1": {"get_star_ts": 1764565800, "star_index": 2}}}}}}"#,
+        )
+        .unwrap();
+        assert_eq!(
+            render(&board, 2025, None),
+            "  1. alex                     4 pts   2*\n\
+             \x20 2. (anonymous user #2)      1 pts   1*\n\
+             \n\
+             Day 1\n\
+             \x20 alex                 part 1   0:05:00  part 2   0:15:00  (+0:10:00)\n\
+             \x20 (anonymous user #2)  part 1   0:10:00  part 2         -\n"
+        );
+        assert!(!render(&board, 2025, Some(2)).contains("Day"));
+    }
+}
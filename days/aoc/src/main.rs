@@ -0,0 +1,301 @@
+use std::path::{Path, PathBuf};
+use std::process::ExitCode;
+
+use clap::{Parser, Subcommand};
+use libaoc::AocError;
+use libaoc::answers::{self, AnswerBook};
+use libaoc::auth::{self, Status};
+use libaoc::leaderboard::LeaderboardError;
+use libaoc::profile::{self, Config};
+
+mod check;
+mod days;
+mod leaderboard;
+mod status;
+
+/// The default `--year`.
+const YEAR: u16 = 2025;
+
+/// Tooling for running and testing Advent of Code solutions.
+#[derive(Debug, Parser)]
+#[command(name = "aoc")]
+struct Cli {
+    /// Account to load inputs with, from `.aoc/profiles.toml`.
+    #[arg(long, global = true)]
+    profile: Option<String>,
+    /// Puzzle year.
+    #[arg(long, global = true, default_value_t = YEAR)]
+    year: u16,
+    #[command(subcommand)]
+    command: Command,
+}
+
+#[derive(Debug, Subcommand)]
+enum Command {
+    /// Print a generated puzzle input for a day.
+    Gen {
+        day: u8,
+        /// Size of the input; its meaning depends on the day (lines, ranges, grid side, ...).
+        #[arg(long)]
+        size: usize,
+        #[arg(long, default_value_t = 0)]
+        seed: u64,
+    },
+    /// Check an input against a day's input format: the given file, or else
+    /// the profile's downloaded input.
+    Lint { day: u8, file: Option<PathBuf> },
+    /// Run every solved day on every cached and generated input, comparing
+    /// with the recorded answers.
+    CheckAll {
+        /// Only check this day.
+        #[arg(long)]
+        day: Option<u8>,
+        /// Number of generated inputs per day.
+        #[arg(long, default_value_t = 3)]
+        seeds: u64,
+        /// Size of each generated input.
+        #[arg(long, default_value_t = 100)]
+        size: usize,
+        /// Record answers for inputs that have none yet.
+        #[arg(long)]
+        record: bool,
+    },
+    /// Show a private leaderboard: standings, star times and the time
+    /// between each day's two stars. Fetched at most every 15 minutes.
+    Leaderboard {
+        /// The leaderboard's id, as in its URL.
+        id: u64,
+        /// Only show this day's times.
+        #[arg(long)]
+        day: Option<u8>,
+    },
+    /// A calendar of solved days, local implementations and recorded answers.
+    Status {
+        /// Don't fetch stars from adventofcode.com.
+        #[arg(long)]
+        offline: bool,
+    },
+    /// Manage the session token.
+    Auth {
+        #[command(subcommand)]
+        command: AuthCommand,
+    },
+}
+
+#[derive(Debug, Subcommand)]
+enum AuthCommand {
+    /// Show who the profile's token is logged in as, and whether it still works.
+    Status,
+}
+
+fn main() -> ExitCode {
+    let cli = Cli::parse();
+    match cli.command {
+        Command::Gen { day, size, seed } => generate(cli.year, day, seed, size),
+        Command::Lint { day, file } => lint(cli.year, day, file, cli.profile.as_deref()),
+        Command::CheckAll {
+            day,
+            seeds,
+            size,
+            record,
+        } => check_all(cli.year, day, seeds, size, record, cli.profile),
+        Command::Leaderboard { id, day } => {
+            show_leaderboard(cli.year, id, day, cli.profile.as_deref())
+        }
+        Command::Status { offline } => show_status(cli.year, offline, cli.profile.as_deref()),
+        Command::Auth {
+            command: AuthCommand::Status,
+        } => auth_status(cli.profile.as_deref()),
+    }
+}
+
+fn generate(year: u16, day: u8, seed: u64, size: usize) -> ExitCode {
+    let Some(generate) = days::generator(year, day) else {
+        eprintln!("No generator for {year} day {day}.");
+        return ExitCode::FAILURE;
+    };
+    print!("{}", generate(seed, size));
+    ExitCode::SUCCESS
+}
+
+fn lint(year: u16, day: u8, file: Option<PathBuf>, profile: Option<&str>) -> ExitCode {
+    let Some(grammar) = days::grammar(year, day) else {
+        eprintln!("No input format for {year} day {day}.");
+        return ExitCode::FAILURE;
+    };
+    let (source, input) = match file {
+        Some(file) => (
+            file.display().to_string(),
+            std::fs::read_to_string(&file).map_err(|e| e.to_string()),
+        ),
+        None => (
+            format!("{year} day {day}"),
+            profile::resolve(profile)
+                .map_err(AocError::from)
+                .and_then(|profile| libaoc::get_input(&profile, year, day))
+                .map_err(|e| e.to_string()),
+        ),
+    };
+    let input = match input {
+        Ok(input) => input,
+        Err(e) => {
+            eprintln!("{source}: {e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    if let Err(e) = grammar.validate(&input) {
+        eprintln!("{source}: {e}");
+        return ExitCode::FAILURE;
+    }
+    ExitCode::SUCCESS
+}
+
+fn check_all(
+    year: u16,
+    day: Option<u8>,
+    seeds: u64,
+    size: usize,
+    record: bool,
+    profile: Option<String>,
+) -> ExitCode {
+    // Without --profile, check everyone's inputs, including the SESSION cache.
+    let profiles = match profile {
+        Some(name) => vec![Some(name)],
+        None => match Config::load(&profile::config_path()) {
+            Ok(config) => config
+                .names()
+                .map(|name| Some(name.to_owned()))
+                .chain([None])
+                .collect(),
+            Err(e) => {
+                eprintln!("{e}");
+                return ExitCode::FAILURE;
+            }
+        },
+    };
+    let mut book = match AnswerBook::load(&answers::answers_path()) {
+        Ok(book) => book,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+
+    let (mut checked, mut failed) = (0, 0);
+    // Every registered day with both parts solved, or just `day` if given.
+    let solved = days::year(year).filter(|&(solved, registered)| {
+        day.is_none_or(|day| day == solved) && registered.solvers().is_some()
+    });
+    for (day, _) in solved {
+        for input in check::inputs(year, day, &profiles, seeds, size) {
+            let [one, two] = check::check(year, day, &input, &mut book, record);
+            checked += 1;
+            if one.is_failure() || two.is_failure() {
+                failed += 1;
+            }
+            println!(
+                "day {day:>2} {:<16} part 1: {one}; part 2: {two}",
+                input.label
+            );
+        }
+    }
+    if record && let Err(e) = book.save() {
+        eprintln!("{e}");
+        return ExitCode::FAILURE;
+    }
+    println!("{checked} inputs checked, {failed} failed");
+    if failed == 0 {
+        ExitCode::SUCCESS
+    } else {
+        ExitCode::FAILURE
+    }
+}
+
+fn auth_status(profile: Option<&str>) -> ExitCode {
+    let profile = match profile::resolve(profile) {
+        Ok(profile) => profile,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    let label = profile.name().unwrap_or("session");
+    match auth::status(profile.session()) {
+        Ok(Status::LoggedIn(user)) => {
+            println!("{label}: valid, logged in as {user}");
+            ExitCode::SUCCESS
+        }
+        Ok(Status::LoggedOut) => {
+            println!("{label}: {}", AocError::SessionExpired);
+            ExitCode::FAILURE
+        }
+        Err(e) => {
+            eprintln!("{label}: {e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn show_leaderboard(year: u16, id: u64, day: Option<u8>, profile: Option<&str>) -> ExitCode {
+    let board = profile::resolve(profile)
+        .map_err(AocError::from)
+        .map_err(LeaderboardError::from)
+        .and_then(|profile| libaoc::leaderboard::load(year, id, profile.session()));
+    match board {
+        Ok(board) => {
+            print!("{}", leaderboard::render(&board, year, day));
+            ExitCode::SUCCESS
+        }
+        Err(e) => {
+            eprintln!("{e}");
+            ExitCode::FAILURE
+        }
+    }
+}
+
+fn show_status(year: u16, offline: bool, profile: Option<&str>) -> ExitCode {
+    let (name, stars) = match profile::resolve(profile) {
+        Ok(resolved) if !offline => {
+            let stars = libaoc::stats::stars(year, resolved.session())
+                .inspect_err(|e| eprintln!("Stars unavailable: {e}"))
+                .ok();
+            (resolved.name().map(str::to_owned), stars)
+        }
+        Ok(resolved) => (resolved.name().map(str::to_owned), None),
+        Err(e) => {
+            if !offline {
+                eprintln!("Stars unavailable: {e}");
+            }
+            (profile.map(str::to_owned), None)
+        }
+    };
+    let book = match AnswerBook::load(&answers::answers_path()) {
+        Ok(book) => book,
+        Err(e) => {
+            eprintln!("{e}");
+            return ExitCode::FAILURE;
+        }
+    };
+    // Registered days say which parts are implemented; crates not linked
+    // into the runner are looked for on disk.
+    let mut code = [None; 25];
+    for (day, registered) in days::year(year) {
+        code[usize::from(day - 1)] = Some(registered.parts.map(|part| part.is_some()));
+    }
+    for (day, code) in (1..).zip(&mut code).filter(|(_, code)| code.is_none()) {
+        *code = status::local_code(Path::new("."), year, day);
+    }
+    let days = std::array::from_fn(|i| {
+        let day = u8::try_from(i + 1).expect("25 days");
+        let answers = book.get(&answers::profile_key(name.as_deref(), year, day));
+        status::DayStatus {
+            stars: stars
+                .as_ref()
+                .map(|stars| stars.get(&day).copied().unwrap_or_default()),
+            code: code[i],
+            answers: [answers.part_1.is_some(), answers.part_2.is_some()],
+        }
+    });
+    print!("{}", status::render(&days));
+    ExitCode::SUCCESS
+}
@@ -0,0 +1,138 @@
+use std::fmt::Write;
+use std::path::{Path, PathBuf};
+
+use libaoc::manifest::Puzzle;
+
+/// What is known about one day, each part in turn.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
+pub struct DayStatus {
+    /// Stars on the site; `None` when they could not be fetched.
+    pub stars: Option<[bool; 2]>,
+    /// Whether each part is implemented, or `None` without a crate or when
+    /// its solvers cannot be found.
+    pub code: Option<[bool; 2]>,
+    /// Whether each part has a recorded answer.
+    pub answers: [bool; 2],
+}
+
+/// Which parts of the crate for `day` of `year` under the workspace `root`
+/// are implemented, or `None` if there is no such crate or it has no
+/// `try_part_N` solvers to look at.
+pub fn local_code(root: &Path, year: u16, day: u8) -> Option<[bool; 2]> {
+    let dir = crate_dir(root, year, day)?;
+    let source = std::fs::read_to_string(dir.join("src/lib.rs")).ok()?;
+    Some([!has_todo(&source, 1)?, !has_todo(&source, 2)?])
+}
+
+/// `years/YEAR/dayNN` or `days/dayNN`, whichever's manifest names the puzzle.
+fn crate_dir(root: &Path, year: u16, day: u8) -> Option<PathBuf> {
+    [
+        root.join(format!("years/{year}/day{day:02}")),
+        root.join(format!("days/day{day:02}")),
+    ]
+    .into_iter()
+    .find(|dir| {
+        std::fs::read_to_string(dir.join("Cargo.toml")).is_ok_and(|manifest| {
+            libaoc::manifest::puzzle(&manifest).is_ok_and(|p| p == Puzzle { year, day })
+        })
+    })
+}
+
+/// Whether `part`'s solver, as copied from the template, is still `todo!()`,
+/// or `None` if there is no `try_part_N` to look at.
+fn has_todo(source: &str, part: u8) -> Option<bool> {
+    let start = source.find(&format!("fn try_part_{part}("))?;
+    let body = &source[start..];
+    let end = body.find("\n}").unwrap_or(body.len());
+    Some(body[..end].contains("todo!("))
+}
+
+/// A calendar of 5 weeks by 5 days; each day shows stars, code and answers
+/// for both parts.
+pub fn render(days: &[DayStatus; 25]) -> String {
+    let mark = |parts: Option<[bool; 2]>, yes: char, no: char| match parts {
+        Some(parts) => parts.iter().map(|&p| if p { yes } else { no }).collect(),
+        None => String::from("  "),
+    };
+    let mut out = String::new();
+    for (week, days) in days.chunks(5).enumerate() {
+        let cells: Vec<_> = days
+            .iter()
+            .enumerate()
+            .map(|(i, status)| {
+                format!(
+                    "{:>2} {} {} {}",
+                    week * 5 + i + 1,
+                    mark(status.stars, '*', '.'),
+                    mark(status.code, '+', 't'),
+                    mark(Some(status.answers), 'a', '.'),
+                )
+            })
+            .collect();
+        writeln!(out, "{}", cells.join("   ")).unwrap();
+    }
+    let count = |f: fn(&DayStatus) -> [bool; 2]| days.iter().flat_map(f).filter(|&p| p).count();
+    writeln!(
+        out,
+        "\n{} stars, {} parts implemented, {} todo, {} answers recorded",
+        count(|d| d.stars.unwrap_or_default()),
+        count(|d| d.code.unwrap_or_default()),
+        count(|d| d.code.map_or([false; 2], |c| c.map(|p| !p))),
+        count(|d| d.answers),
+    )
+    .unwrap();
+    out.push_str(
+        "* solved  + implemented  t todo!()  a answer recorded  (blank: unknown or no crate)\n",
+    );
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn finds_todos() {
+        let template = include_str!("../../../template/src/lib.rs");
+        assert_eq!([1, 2].map(|part| has_todo(template, part)), [Some(true); 2]);
+        let half = "pub fn try_part_1(input: &str) -> Result<usize, InputError> {\n    Ok(input.len())\n}\n\n\
+                    pub fn try_part_2(_input: &str) -> Result<usize, InputError> {\n    todo!()\n}\n";
+        assert_eq!(
+            [1, 2].map(|part| has_todo(half, part)),
+            [Some(false), Some(true)]
+        );
+        let ported = "pub fn part_1(input: &str) -> usize {\n    input.len()\n}\n";
+        assert_eq!(has_todo(ported, 1), None);
+        let root = Path::new("../..");
+        assert_eq!(local_code(root, 2025, 1), Some([true, true]));
+        assert_eq!(local_code(root, 2024, 1), Some([true, true]));
+        assert_eq!(local_code(root, 2024, 2), None);
+        assert_eq!(local_code(root, 2025, 25), None);
+    }
+
+    #[test]
+    fn renders_a_calendar() {
+        let mut days = [DayStatus::default(); 25];
+        days[0] = DayStatus {
+            stars: Some([true, true]),
+            code: Some([true, true]),
+            answers: [true, true],
+        };
+        days[1] = DayStatus {
+            stars: Some([true, false]),
+            code: Some([true, false]),
+            answers: [true, false],
+        };
+        let calendar = render(&days);
+        let mut lines = calendar.lines();
+        assert_eq!(
+            lines.next().unwrap(),
+            " 1 ** ++ aa    2 *. +t a.    3       ..    4       ..    5       .."
+        );
+        assert_eq!(lines.nth(4).unwrap(), "");
+        assert_eq!(
+            lines.next().unwrap(),
+            "3 stars, 3 parts implemented, 1 todo, 3 answers recorded"
+        );
+    }
+}
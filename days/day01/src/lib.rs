@@ -1,28 +1,33 @@
 use std::ops::{Add, Sub};
+
+use libaoc::InputError;
+use libaoc::aoc;
+use libaoc::grammar::Grammar;
+use libaoc::rng::Rng;
+
+/// A dial that also counts how often it pointed at zero during its last turn.
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq)]
-struct ZeroClickingDial {
-    dial: Dial,
-    clicks: i32,
+struct ZeroClickingDial<const N: usize = 100> {
+    dial: Dial<N>,
+    clicks: usize,
 }
 
-impl From<Dial> for ZeroClickingDial {
-    fn from(dial: Dial) -> Self {
+impl<const N: usize> From<Dial<N>> for ZeroClickingDial<N> {
+    fn from(dial: Dial<N>) -> Self {
         ZeroClickingDial { dial, clicks: 0 }
     }
 }
 
-impl Add<i32> for ZeroClickingDial {
+impl<const N: usize> Add<i32> for ZeroClickingDial<N> {
     type Output = Self;
 
     fn add(self, rhs: i32) -> Self::Output {
-        let dir = rhs.signum();
-        let clicks = (rhs * dir + (self.dial.0 * dir).rem_euclid(100)).div_euclid(100);
-        let dial = self.dial + rhs;
+        let (dial, clicks) = self.dial.turn(rhs);
         Self { dial, clicks }
     }
 }
 
-impl Sub<i32> for ZeroClickingDial {
+impl<const N: usize> Sub<i32> for ZeroClickingDial<N> {
     type Output = Self;
 
     fn sub(self, rhs: i32) -> Self::Output {
@@ -30,104 +35,313 @@ impl Sub<i32> for ZeroClickingDial {
     }
 }
 
+/// A dial numbered `0..N`. Positive rotations turn right (up), negative
+/// rotations turn left (down), wrapping around at `N`.
 #[derive(Clone, Copy, Debug, PartialEq, Eq)]
-struct Dial(i32);
+pub struct Dial<const N: usize = 100>(usize);
+
+impl<const N: usize> Dial<N> {
+    /// A dial pointing at `start`.
+    ///
+    /// # Panics
+    /// If `start` is not a position on the dial.
+    #[must_use]
+    pub fn new(start: usize) -> Self {
+        assert!(start < N, "start {start} is not on a dial of size {N}");
+        Self(start)
+    }
 
-impl Default for Dial {
+    #[must_use]
+    pub fn position(self) -> usize {
+        self.0
+    }
+
+    /// Turn by `rotation`, returning the new dial and the number of clicks
+    /// during the turn (including the last) that landed on zero.
+    #[must_use]
+    pub fn turn(self, rotation: i32) -> (Self, usize) {
+        let distance = rotation.unsigned_abs() as usize;
+        // Distance to the first zero in the direction of travel, treating a
+        // dial already at zero as needing a full turn.
+        let to_zero = match rotation.signum() {
+            1 => N - self.0,
+            _ => (self.0 + N - 1) % N + 1,
+        };
+        let zeros = if distance < to_zero {
+            0
+        } else {
+            1 + (distance - to_zero) / N
+        };
+        (self + rotation, zeros)
+    }
+
+    /// The `(position, zeros)` after each rotation, where `zeros` counts the
+    /// clicks of that rotation that landed on zero.
+    pub fn trace(
+        self,
+        rotations: impl IntoIterator<Item = i32>,
+    ) -> impl Iterator<Item = (usize, usize)> {
+        rotations
+            .into_iter()
+            .scan(ZeroClickingDial::from(self), |dial, rotation| {
+                *dial = *dial + rotation;
+                Some((dial.dial.0, dial.clicks))
+            })
+    }
+}
+
+impl<const N: usize> Default for Dial<N> {
     fn default() -> Self {
-        Self(50)
+        Self(N / 2)
     }
 }
 
-impl Add<i32> for Dial {
+impl<const N: usize> Add<i32> for Dial<N> {
     type Output = Self;
 
     fn add(self, rhs: i32) -> Self::Output {
-        assert!(self.0 < 100);
-        let val = (self.0 + rhs).rem_euclid(100);
-        assert!(val < 100);
+        let distance = rhs.unsigned_abs() as usize % N;
+        let val = if rhs < 0 {
+            (self.0 + N - distance) % N
+        } else {
+            (self.0 + distance) % N
+        };
         Dial(val)
     }
 }
 
-fn parse_line(line: &str) -> i32 {
-    let sign = match line.chars().next() {
-        Some('L') => -1,
-        Some('R') => 1,
-        Some(_) | None => unreachable!(),
+/// Parse `L68` or `R48` into a signed rotation, or `None` if malformed.
+fn parse_line(line: &str) -> Option<i32> {
+    let (sign, distance) = match line.split_at_checked(1)? {
+        ("L", distance) => (-1, distance),
+        ("R", distance) => (1, distance),
+        _ => return None,
     };
-    line[1..].parse::<i32>().unwrap() * sign
+    // `i32::from_str` accepts a sign, which would let `L-5` turn right.
+    if !distance.bytes().all(|b| b.is_ascii_digit()) {
+        return None;
+    }
+    Some(distance.parse::<i32>().ok()? * sign)
 }
 
-pub fn part_1(input: &str) -> usize {
+/// The signed rotations listed in the input, left being negative.
+pub fn rotations(input: &str) -> Result<Vec<i32>, InputError> {
     input
         .lines()
-        .map(parse_line)
-        .scan(Dial::default(), |dial, rot| {
-            *dial = *dial + rot;
-            Some(*dial)
+        .enumerate()
+        .map(|(i, line)| {
+            parse_line(line)
+                .ok_or_else(|| InputError::malformed(i + 1, format!("invalid rotation {line:?}")))
         })
-        .filter(|r| *r == Dial(0))
-        .collect::<Vec<_>>()
-        .len()
+        .collect()
+}
+
+/// One rotation per line, e.g. `L68`.
+#[aoc(grammar)]
+pub fn grammar() -> Grammar {
+    Grammar::lines(r"[LR]\d+")
+}
+
+#[aoc(part = 1)]
+pub fn try_part_1(input: &str) -> Result<usize, InputError> {
+    Ok(Dial::<100>::default()
+        .trace(rotations(input)?)
+        .filter(|&(position, _)| position == 0)
+        .count())
+}
+
+#[aoc(part = 2)]
+pub fn try_part_2(input: &str) -> Result<usize, InputError> {
+    Ok(Dial::<100>::default()
+        .trace(rotations(input)?)
+        .map(|(_, zeros)| zeros)
+        .sum())
+}
+
+pub fn part_1(input: &str) -> usize {
+    try_part_1(input).unwrap_or_else(|e| panic!("{e}"))
 }
 
 pub fn part_2(input: &str) -> usize {
-    input
-        .lines()
-        .map(parse_line)
-        .scan(ZeroClickingDial::default(), |dial, rot| {
-            *dial = *dial + rot;
-            Some(*dial)
-        })
-        .map(|d| d.clicks)
-        .sum::<i32>()
-        .try_into()
-        .expect("Expected positive value")
+    try_part_2(input).unwrap_or_else(|e| panic!("{e}"))
 }
 const _: () = assert!(usize::BITS >= 32, "usize must be at least 32 bits");
 
+/// A seeded puzzle input of `size` rotations.
+#[aoc(generator)]
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+    (0..size)
+        .map(|_| format!("{}{}\n", rng.pick(&['L', 'R']), rng.range(1..1000)))
+        .collect()
+}
+
 #[cfg(test)]
 mod test {
     use super::*;
+    use libaoc::testing::{self, assert_equivalent, rotation_lines};
 
     #[test]
     fn test_clicking_dial() {
-        let dial = ZeroClickingDial::from(Dial(10)) + 10;
+        let dial = ZeroClickingDial::from(Dial::<100>(10)) + 10;
         assert!(dial.clicks == 0);
         assert!(dial.dial.0 == 20);
-        let dial = ZeroClickingDial::from(Dial(0)) + 10;
+        let dial = ZeroClickingDial::from(Dial::<100>(0)) + 10;
         assert!(dial.clicks == 0);
         assert!(dial.dial.0 == 10);
-        let dial = ZeroClickingDial::from(Dial(0)) + 100;
+        let dial = ZeroClickingDial::from(Dial::<100>(0)) + 100;
         assert!(dial.clicks == 1);
         assert!(dial.dial.0 == 0);
-        let dial = ZeroClickingDial::from(Dial(0)) - 100;
+        let dial = ZeroClickingDial::from(Dial::<100>(0)) - 100;
         assert!(dial.clicks == 1);
         assert!(dial.dial.0 == 0);
-        let dial = ZeroClickingDial::from(Dial(0)) - 110;
+        let dial = ZeroClickingDial::from(Dial::<100>(0)) - 110;
         assert!(dial.clicks == 1);
         assert!(dial.dial.0 == 90);
-        let dial = ZeroClickingDial::from(Dial(0)) + 110;
+        let dial = ZeroClickingDial::from(Dial::<100>(0)) + 110;
         assert!(dial.clicks == 1);
         assert!(dial.dial.0 == 10);
-        let dial = ZeroClickingDial::from(Dial(50)) - 50;
+        let dial = ZeroClickingDial::from(Dial::<100>(50)) - 50;
         assert!(dial.clicks == 1);
         assert!(dial.dial.0 == 0);
-        let dial = ZeroClickingDial::from(Dial(50)) + 50;
+        let dial = ZeroClickingDial::from(Dial::<100>(50)) + 50;
         assert!(dial.clicks == 1);
         assert!(dial.dial.0 == 0);
-        let dial = ZeroClickingDial::from(Dial(50)) + 150;
+        let dial = ZeroClickingDial::from(Dial::<100>(50)) + 150;
         assert!(dial.clicks == 2);
         assert!(dial.dial.0 == 0);
-        let dial = ZeroClickingDial::from(Dial(50)) - 150;
+        let dial = ZeroClickingDial::from(Dial::<100>(50)) - 150;
         assert!(dial.clicks == 2);
         assert!(dial.dial.0 == 0);
-        let dial = ZeroClickingDial::from(Dial(0)) - 1000;
+        let dial = ZeroClickingDial::from(Dial::<100>(0)) - 1000;
         assert!(dial.clicks == 10);
         assert!(dial.dial.0 == 0);
-        let dial = ZeroClickingDial::from(Dial(0)) + 1050;
+        let dial = ZeroClickingDial::from(Dial::<100>(0)) + 1050;
         assert!(dial.clicks == 10);
         assert!(dial.dial.0 == 50);
     }
+
+    /// Reference: turn one click at a time, counting landings on zero.
+    fn turn_clicking<const N: usize>(dial: Dial<N>, rotation: i32) -> (Dial<N>, usize) {
+        let step = rotation.signum();
+        let mut dial = dial;
+        let mut zeros = 0;
+        for _ in 0..rotation.unsigned_abs() {
+            dial = dial + step;
+            zeros += usize::from(dial.0 == 0);
+        }
+        (dial, zeros)
+    }
+
+    fn check_against_clicking<const N: usize>() {
+        for start in 0..N {
+            for rotation in -3 * N as i32..=3 * N as i32 {
+                let dial = Dial::<N>::new(start);
+                assert_eq!(
+                    dial.turn(rotation),
+                    turn_clicking(dial, rotation),
+                    "N = {N}, start = {start}, rotation = {rotation}"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn turn_matches_clicking() {
+        check_against_clicking::<1>();
+        check_against_clicking::<2>();
+        check_against_clicking::<7>();
+        check_against_clicking::<100>();
+    }
+
+    #[test]
+    fn zero_clicking_matches_clicking() {
+        assert_equivalent(
+            (0..100usize, testing::rotations(0..50, 1000)),
+            |(start, rotations)| {
+                let dial = ZeroClickingDial::from(Dial::<100>::new(*start));
+                rotations
+                    .iter()
+                    .scan(dial, |dial, &r| {
+                        *dial = *dial + r;
+                        Some((dial.dial.0, dial.clicks))
+                    })
+                    .collect::<Vec<_>>()
+            },
+            |(start, rotations)| {
+                rotations
+                    .iter()
+                    .scan(Dial::<100>::new(*start), |dial, &r| {
+                        let (next, zeros) = turn_clicking(*dial, r);
+                        *dial = next;
+                        Some((next.0, zeros))
+                    })
+                    .collect::<Vec<_>>()
+            },
+        );
+    }
+
+    #[test]
+    fn part_2_matches_clicking() {
+        assert_equivalent(
+            testing::rotations(0..50, 1000),
+            |rotations| part_2(&rotation_lines(rotations)),
+            |rotations| {
+                let mut dial = Dial::<100>::default();
+                let mut total = 0;
+                for &r in rotations {
+                    let (next, zeros) = turn_clicking(dial, r);
+                    dial = next;
+                    total += zeros;
+                }
+                total
+            },
+        );
+    }
+
+    #[test]
+    fn example() {
+        let input = "L68\nL30\nR48\nL5\nR60\nL55\nL1\nL99\nR14\nL82\n";
+        assert_eq!(part_1(input), 3);
+        assert_eq!(part_2(input), 6);
+        let trace: Vec<_> = Dial::<100>::default()
+            .trace(rotations(input).unwrap())
+            .collect();
+        assert_eq!(trace[..3], [(82, 1), (52, 0), (0, 1)]);
+        let small: Vec<_> = Dial::<10>::new(0).trace([25, -5, -1]).collect();
+        assert_eq!(small, [(5, 2), (0, 1), (9, 0)]);
+    }
+
+    #[test]
+    fn invalid_rotations_are_errors() {
+        for line in ["", "L", "X5", "L-5", "R+5", "R2147483648", "ÉR5"] {
+            assert_eq!(
+                try_part_1(&format!("R1\n{line}\nL1\n")),
+                Err(InputError::malformed(
+                    2,
+                    format!("invalid rotation {line:?}")
+                ))
+            );
+        }
+        assert_eq!(try_part_2("R2147483647\nL2147483647\n"), Ok(42_949_672));
+    }
+
+    #[test]
+    fn generated_input() {
+        let input = libaoc::testing::generated_input(generate, &grammar(), 200);
+        assert_eq!(input.lines().count(), 200);
+        let mut dial = Dial::<100>::default();
+        let mut zeros = 0;
+        for r in rotations(&input).unwrap() {
+            let (next, z) = turn_clicking(dial, r);
+            dial = next;
+            zeros += z;
+        }
+        assert_eq!(part_2(&input), zeros);
+    }
+
+    #[test]
+    fn arbitrary_input_does_not_panic() {
+        testing::assert_no_panic(&[try_part_1, try_part_2]);
+    }
 }
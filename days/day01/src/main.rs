@@ -1,4 +1,3 @@
-use day01::{part_1, part_2};
-pub fn main() {
-    libaoc::evaluate(part_1, part_2, 2025, 1);
-}
+use day01::{grammar, part_1, part_2};
+
+libaoc::aoc_main!(part_1, part_2, grammar);
@@ -1,97 +1,264 @@
+use libaoc::InputError;
+use libaoc::aoc;
+use libaoc::grammar::Grammar;
+use libaoc::math::{digit_count, divisors, pow10, repunit};
+use libaoc::rng::Rng;
 
 /// Parse the range strings "123-456" into 2 usize values.
-fn parse_range(range: &str) -> (usize, usize) {
-    let range: Vec<_> = range
-        .split('-')
-        .map(|s| s.parse::<usize>().unwrap())
-        .collect();
-    (range[0], range[1])
+///
+/// Returns `None` unless both ends are numbers and the start is not after
+/// the end.
+fn parse_range(range: &str) -> Option<(usize, usize)> {
+    let (a, b) = range.split_once('-')?;
+    let (a, b) = (a.parse().ok()?, b.parse().ok()?);
+    (a <= b).then_some((a, b))
+}
+
+/// Parse the comma-separated ranges on the single input line.
+fn parse(input: &str) -> Result<Vec<(usize, usize)>, InputError> {
+    input
+        .trim_end()
+        .split(',')
+        .map(|range| {
+            parse_range(range)
+                .ok_or_else(|| InputError::malformed(1, format!("invalid ID range {range:?}")))
+        })
+        .collect()
 }
 
-/// Test if a value has a repeating digit pattern of length 2
-#[allow(clippy::trivially_copy_pass_by_ref)]
-fn is_double(x: &usize) -> bool {
-    let x = format!("{x}");
-    let len = x.len();
-    if len.is_multiple_of(2) {
-        let (left, right) = x.split_at(len / 2);
-        left == right
-    } else {
-        false
+/// Split a range into sub-ranges that each hold values of a single digit count.
+fn by_digit_count(range: (usize, usize)) -> impl Iterator<Item = (u32, (usize, usize))> {
+    let (a, b) = range;
+    (digit_count(a)..=digit_count(b)).filter_map(move |d| {
+        let lo = pow10(d - 1)?.max(a);
+        let hi = pow10(d).map_or(usize::MAX, |p| p - 1).min(b);
+        (lo <= hi).then_some((d, (lo, hi)))
+    })
+}
+
+/// Sum the values in `range` made of a `len`-digit block repeated `reps` times.
+///
+/// Those values are `pattern * repunit(len, reps)` for each `len`-digit
+/// pattern, so the patterns in range form an arithmetic series.
+///
+/// Returns `None` if the sum does not fit in a usize.
+fn sum_repetitions(range: (usize, usize), len: u32, reps: u32) -> Option<usize> {
+    let (a, b) = range;
+    let Some(r) = repunit(len, reps) else {
+        return Some(0);
+    };
+    let Some(first) = pow10(len - 1) else {
+        return Some(0);
+    };
+    let last = pow10(len).map_or(usize::MAX, |p| p - 1);
+    let lo = first.max(a.div_ceil(r));
+    let hi = last.min(b / r);
+    if lo > hi {
+        return Some(0);
     }
+    let (lo, hi, r) = (lo as u128, hi as u128, r as u128);
+    let sum = r * (lo + hi) * (hi - lo + 1) / 2;
+    usize::try_from(sum).ok()
 }
 
-/// Test if a value has a repeating digit pattern of length n
-fn repeats_n(s: &str, n: usize) -> bool {
-    let f = s.len() / n;
-    let base = s.get(..f).expect("f should be smaller than length");
-    let cmp = base.repeat(n);
-    s == cmp
+/// Sum the values in `range` that repeat n=2 times in pattern
+fn sum_doubles(range: (usize, usize)) -> Option<usize> {
+    by_digit_count(range)
+        .filter(|(d, _)| d.is_multiple_of(2))
+        .try_fold(0usize, |acc, (d, range)| {
+            acc.checked_add(sum_repetitions(range, d / 2, 2)?)
+        })
 }
 
-/// Assess is a value has a repeating digit pattern of length n>=2
-#[allow(clippy::trivially_copy_pass_by_ref)]
-fn is_repeat(x: &usize) -> bool {
-    let s = format!("{x}");
-    let len = s.len();
-    (2..=len)
-        .take_while(|n| n * n <= *x)
-        .filter(|n| len.is_multiple_of(*n))
-        .any(|f| repeats_n(&s, f))
+/// Sum the values in `range` that repeat n>=2 times in pattern
+///
+/// A value built from a block of length `l` is also built from any block
+/// length that `l` divides, so each value is counted only under its shortest
+/// block: `exact[l]` is the sum for block length `l` minus the sums already
+/// attributed to the proper divisors of `l`.
+fn sum_repeats(range: (usize, usize)) -> Option<usize> {
+    by_digit_count(range).try_fold(0usize, |total, (d, range)| {
+        let lens = divisors(d as usize);
+        let mut exact = Vec::with_capacity(lens.len());
+        for &len in &lens[..lens.len() - 1] {
+            // Each `exact` sum is a subset of this one, so only this can overflow.
+            let shorter: usize = lens
+                .iter()
+                .zip(&exact)
+                .filter(|&(&l, _)| len.is_multiple_of(l))
+                .map(|(_, &s)| s)
+                .sum();
+            let len = len as u32;
+            exact.push(sum_repetitions(range, len, d / len)? - shorter);
+        }
+        exact
+            .iter()
+            .try_fold(total, |total, &s| total.checked_add(s))
+    })
 }
 
-/// Find values that repeat n=2 times in pattern
-fn find_doubles(range: (usize, usize)) -> Vec<usize> {
-    let (a, b) = range;
-    (a..=b).filter(is_double).collect()
+/// Apply `sum` to every range and add the results, failing on overflow.
+fn total(input: &str, sum: fn((usize, usize)) -> Option<usize>) -> Result<usize, InputError> {
+    parse(input)?
+        .into_iter()
+        .try_fold(0usize, |total, range| total.checked_add(sum(range)?))
+        .ok_or_else(|| InputError::Unsolvable("sum of invalid IDs overflows a usize".into()))
 }
 
-/// Find values that repeat n>=2 times in pattern
-fn find_repeats(range: (usize, usize)) -> Vec<usize> {
-    let (a, b) = range;
-    (a..=b).filter(is_repeat).collect()
+/// A single line of comma-separated ID ranges.
+#[aoc(grammar)]
+pub fn grammar() -> Grammar {
+    Grammar::lines(r"\d+-\d+(,\d+-\d+)*")
+}
+
+#[aoc(part = 1)]
+pub fn try_part_1(input: &str) -> Result<usize, InputError> {
+    total(input, sum_doubles)
+}
+
+#[aoc(part = 2)]
+pub fn try_part_2(input: &str) -> Result<usize, InputError> {
+    total(input, sum_repeats)
 }
 
 pub fn part_1(input: &str) -> usize {
-    input
-        .trim_end()
-        .split(',')
-        .map(parse_range)
-        .flat_map(find_doubles)
-        .sum()
+    try_part_1(input).unwrap_or_else(|e| panic!("{e}"))
 }
 
 pub fn part_2(input: &str) -> usize {
-    input
-        .trim_end()
-        .split(',')
-        .map(parse_range)
-        .flat_map(find_repeats)
-        .sum()
+    try_part_2(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+/// A seeded puzzle input of `size` comma-separated ID ranges.
+#[aoc(generator)]
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+    let ranges: Vec<String> = (0..size)
+        .map(|_| {
+            let digits = rng.range(1..11) as u32;
+            let first = pow10(digits - 1).expect("10 digits fit in a usize");
+            let lo = rng.range(first..first * 10);
+            let hi = lo + rng.range(0..lo / 10 + 100);
+            format!("{lo}-{hi}")
+        })
+        .collect();
+    ranges.join(",") + "\n"
 }
 
 #[cfg(test)]
 #[allow(clippy::unreadable_literal)]
 mod test {
     use super::*;
+    use libaoc::testing::{assert_equivalent, digit_string};
+    use proptest::prelude::*;
+
+    /// Brute-force reference: format every value and compare its halves.
+    fn find_doubles(range: (usize, usize)) -> Vec<usize> {
+        let (a, b) = range;
+        (a..=b)
+            .filter(|x| {
+                let x = format!("{x}");
+                let (left, right) = x.split_at(x.len() / 2);
+                x.len().is_multiple_of(2) && left == right
+            })
+            .collect()
+    }
+
+    /// Brute-force reference: format every value and try each repeat count.
+    fn find_repeats(range: (usize, usize)) -> Vec<usize> {
+        let (a, b) = range;
+        (a..=b)
+            .filter(|x| {
+                let s = format!("{x}");
+                let len = s.len();
+                (2..=len)
+                    .filter(|n| len.is_multiple_of(*n))
+                    .any(|n| s[..len / n].repeat(n) == s)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn sum_repeats_matches_strings() {
+        // Uniformly random ranges rarely hold a repeat, so build ranges
+        // around one.
+        let ranges = (
+            digit_string(1..=6, b'0'..=b'9'),
+            2..=3usize,
+            0..500usize,
+            0..500usize,
+        )
+            .prop_map(|(block, reps, below, above)| {
+                let value: usize = block.repeat(reps).parse().unwrap();
+                (value.saturating_sub(below), value + above)
+            });
+        assert_equivalent(
+            ranges,
+            |&range| sum_repeats(range),
+            |&range| Some(find_repeats(range).iter().sum()),
+        );
+    }
+    #[test]
+    fn example() {
+        let input = "11-22,95-115,998-1012,1188511880-1188511890,222220-222224,\
+            1698522-1698528,446443-446449,38593856-38593862,565653-565659,\
+            824824821-824824827,2121212118-2121212124\n";
+        assert_eq!(part_1(input), 1227775554);
+        assert_eq!(part_2(input), 4174379265);
+    }
+    #[test]
+    fn sums_near_usize_max() {
+        let range = (usize::MAX - 1_000, usize::MAX);
+        assert_eq!(sum_doubles(range), Some(0));
+        assert_eq!(sum_repeats(range), Some(0));
+    }
     #[test]
-    fn test_repeat() {
-        assert!(is_repeat(&11));
-        assert!(is_repeat(&111));
-        assert!(is_repeat(&1111));
-        assert!(is_repeat(&12_12_12_12_12));
-        assert!(is_repeat(&123_123_123_123));
-        assert!(is_repeat(&1234_1234_1234_1234));
-        assert!(is_repeat(&1234_5678_1234_5678));
+    fn invalid_input_is_an_error() {
+        for input in ["", "1-", "-2", "5-3", "1-2,,3-4", "a-b\n", "1-2-3"] {
+            assert!(
+                matches!(
+                    try_part_1(input),
+                    Err(InputError::Malformed { line: 1, .. })
+                ),
+                "{input:?}"
+            );
+        }
+        assert!(matches!(
+            try_part_2("1-18446744073709551615"),
+            Err(InputError::Unsolvable(_))
+        ));
     }
+
+    proptest! {
+        #[test]
+        fn doubles_match_brute_force(a in 0usize..100_000_000, width in 0usize..20_000) {
+            let range = (a, a + width);
+            prop_assert_eq!(sum_doubles(range), Some(find_doubles(range).iter().sum()));
+        }
+
+        #[test]
+        fn repeats_match_brute_force(a in 0usize..100_000_000, width in 0usize..20_000) {
+            let range = (a, a + width);
+            prop_assert_eq!(sum_repeats(range), Some(find_repeats(range).iter().sum()));
+        }
+
+    }
+
+    #[test]
+    fn generated_input() {
+        let input = libaoc::testing::generated_input(generate, &grammar(), 50);
+        assert_eq!(input.trim_end().split(',').count(), 50);
+        let small = generate(3, 20);
+        for range in parse(&small).unwrap() {
+            if range.1 - range.0 >= 100_000 {
+                continue;
+            }
+            assert_eq!(sum_repeats(range), Some(find_repeats(range).iter().sum()));
+        }
+    }
+
     #[test]
-    fn test_not_repeat() {
-        assert!(!is_repeat(&10));
-        assert!(!is_repeat(&101));
-        assert!(!is_repeat(&1011));
-        assert!(!is_repeat(&11_12_12_12_12));
-        assert!(!is_repeat(&113_123_123_123));
-        assert!(!is_repeat(&1134_1234_1234_1234));
-        assert!(!is_repeat(&1134_5678_1234_5678));
+    fn arbitrary_input_does_not_panic() {
+        libaoc::testing::assert_no_panic(&[try_part_1, try_part_2]);
     }
 }
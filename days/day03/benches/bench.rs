@@ -1,14 +1,19 @@
 use criterion::{Criterion, criterion_group, criterion_main};
-use day03::{part_1, part_2};
+use day03::{generate, part_1, part_2};
 use libaoc::load_input;
+use libaoc::testing;
 
 fn bench(c: &mut Criterion) {
-    let day = 3;
-    let year = 2025;
-    let input = load_input(year, day);
+    let puzzle = libaoc::puzzle!();
+    let input = load_input(puzzle.year, puzzle.day);
     c.bench_function("part_1", |b| b.iter(|| part_1(&input)));
     c.bench_function("part_2", |b| b.iter(|| part_2(&input)));
 }
 
-criterion_group!(benches, bench);
+/// Solve generated inputs of increasing size to see how each part scales.
+fn scaling(c: &mut Criterion) {
+    testing::scaling(c, generate, &[100, 1_000, 10_000], [part_1, part_2]);
+}
+
+criterion_group!(benches, scaling, bench);
 criterion_main!(benches);
@@ -1,55 +1,202 @@
-fn max_joltage(line: &str) -> usize {
-    let len = line.len();
-    let (i, tens) = first_max(&line[..len - 1]);
-    let (_, ones) = first_max(&line[i + 1..]);
-    let val = tens.to_digit(10).unwrap() * 10 + ones.to_digit(10).unwrap();
-    val as usize
+use libaoc::InputError;
+use libaoc::aoc;
+use libaoc::grammar::Grammar;
+use libaoc::rng::Rng;
+
+/// The largest `k` digits of `line` that can be kept in order, found with a
+/// monotonic stack in a single pass.
+///
+/// Returns `None` if `line` has fewer than `k` digits or contains a non-digit.
+pub fn max_subsequence_digits(line: &str, k: usize) -> Option<Vec<u8>> {
+    let line = line.as_bytes();
+    if line.len() < k || !line.iter().all(u8::is_ascii_digit) {
+        return None;
+    }
+    let mut drops = line.len() - k;
+    let mut stack = Vec::with_capacity(line.len());
+    for &digit in line {
+        while drops > 0 && stack.last().is_some_and(|&top| top < digit) {
+            stack.pop();
+            drops -= 1;
+        }
+        stack.push(digit);
+    }
+    stack.truncate(k);
+    Some(stack.iter().map(|d| d - b'0').collect())
 }
 
-#[inline]
-fn first_max(s: &str) -> (usize, char) {
-    s.chars()
+/// The largest `k`-digit number that can be formed by keeping digits of
+/// `line` in order.
+///
+/// Returns `None` under the same conditions as [`max_subsequence_digits`], or
+/// if the result does not fit in a `u128` (more than 38 digits).
+pub fn max_subsequence_number(line: &str, k: usize) -> Option<u128> {
+    max_subsequence_digits(line, k)?
+        .into_iter()
+        .try_fold(0u128, |acc, d| {
+            acc.checked_mul(10)?.checked_add(u128::from(d))
+        })
+}
+
+fn total_joltage(input: &str, k: usize) -> Result<usize, InputError> {
+    let overflow = || InputError::Unsolvable("total joltage overflows a usize".into());
+    input
+        .lines()
         .enumerate()
-        .fold((0, '0'), |acc, (i, c)| if c > acc.1 { (i, c) } else { acc })
-}
-fn recursive_joltage(line: &str, mut remaining: usize, mut value: usize) -> (&str, usize, usize) {
-    // Base case
-    if remaining == 0 {
-        return (line, remaining, value);
-    }
-    let len = line.len();
-    // len = 6 (0..6), rem=4, len-rem=2  (0, 1, 2, 3, 4, 5), 4, 0
-    // [0..=2] = (0,1,2) len 3, max=2, i=2, left (3, 4, 5) (new rem = 3)
-    // len = 3 (1..4), rem=3, len-rem=0 (1,2,3), 3, 0
-    // [0..=0] = (1,) len 1
-    let (i, next_digit) = first_max(&line[..=len - remaining]);
-    remaining -= 1;
-    value = 10 * value
-        + usize::try_from(
-            next_digit
-                .to_digit(10)
-                .expect("The input strings should all be valid base-10 (radix-10) digits"),
-        )
-        .expect("A single digit should be a valid usize");
-    recursive_joltage(&line[i + 1..], remaining, value)
+        .try_fold(0usize, |total, (i, line)| {
+            let joltage = max_subsequence_number(line, k).ok_or_else(|| {
+                InputError::malformed(i + 1, format!("unable to pick {k} digits from {line:?}"))
+            })?;
+            let joltage = usize::try_from(joltage).map_err(|_| overflow())?;
+            total.checked_add(joltage).ok_or_else(overflow)
+        })
+}
+
+/// One battery bank per line, with enough digits for part 2.
+#[aoc(grammar)]
+pub fn grammar() -> Grammar {
+    Grammar::lines(r"\d{12,}")
+}
+
+#[aoc(part = 1)]
+pub fn try_part_1(input: &str) -> Result<usize, InputError> {
+    total_joltage(input, 2)
+}
+
+#[aoc(part = 2)]
+pub fn try_part_2(input: &str) -> Result<usize, InputError> {
+    total_joltage(input, 12)
 }
 
 pub fn part_1(input: &str) -> usize {
-    input.lines().map(max_joltage).sum()
+    try_part_1(input).unwrap_or_else(|e| panic!("{e}"))
 }
 
 pub fn part_2(input: &str) -> usize {
-    input
-        .lines()
-        .map(|line| recursive_joltage(line, 12, 0).2)
-        .sum()
+    try_part_2(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+/// A seeded puzzle input of `size` battery banks of 100 digits each.
+#[aoc(generator)]
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+    (0..size)
+        .map(|_| {
+            let mut bank: String = (0..100)
+                .map(|_| char::from(b'0' + rng.range(1..10) as u8))
+                .collect();
+            bank.push('\n');
+            bank
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use proptest::prelude::*;
+
+    fn max_joltage(line: &str) -> usize {
+        let len = line.len();
+        let (i, tens) = first_max(&line[..len - 1]);
+        let (_, ones) = first_max(&line[i + 1..]);
+        let val = tens.to_digit(10).unwrap() * 10 + ones.to_digit(10).unwrap();
+        val as usize
+    }
+
+    #[inline]
+    fn first_max(s: &str) -> (usize, char) {
+        s.chars()
+            .enumerate()
+            .fold((0, '0'), |acc, (i, c)| if c > acc.1 { (i, c) } else { acc })
+    }
+
+    fn recursive_joltage(
+        line: &str,
+        mut remaining: usize,
+        mut value: usize,
+    ) -> (&str, usize, usize) {
+        if remaining == 0 {
+            return (line, remaining, value);
+        }
+        let len = line.len();
+        let (i, next_digit) = first_max(&line[..=len - remaining]);
+        remaining -= 1;
+        value = 10 * value + next_digit.to_digit(10).unwrap() as usize;
+        recursive_joltage(&line[i + 1..], remaining, value)
+    }
+
     #[test]
     fn joltage() {
         assert_eq!(max_joltage("1234"), 34);
+        assert_eq!(max_subsequence_number("1234", 2), Some(34));
+    }
+
+    #[test]
+    fn example() {
+        let input = "987654321111111
+811111111111119
+234234234234278
+818181911112111
+";
+        assert_eq!(part_1(input), 357);
+        assert_eq!(part_2(input), 3121910778619);
+    }
+
+    #[test]
+    fn edge_cases() {
+        assert_eq!(max_subsequence_number("", 0), Some(0));
+        assert_eq!(max_subsequence_number("12", 3), None);
+        assert_eq!(max_subsequence_number("1a3", 2), None);
+        let nines = "9".repeat(40);
+        assert_eq!(max_subsequence_number(&nines, 38), Some(10u128.pow(38) - 1));
+        assert_eq!(max_subsequence_number(&nines, 39), None);
+        assert_eq!(
+            max_subsequence_digits(&nines, 39).map(|d| d.len()),
+            Some(39)
+        );
+    }
+
+    #[test]
+    fn invalid_lines_are_errors() {
+        assert_eq!(
+            try_part_1("12\n\n34\n"),
+            Err(InputError::malformed(
+                2,
+                "unable to pick 2 digits from \"\""
+            ))
+        );
+        assert!(matches!(
+            try_part_2("123456789012\n12345x789012\n"),
+            Err(InputError::Malformed { line: 2, .. })
+        ));
+    }
+
+    proptest! {
+        #[test]
+        fn matches_first_max_pair(line in "[1-9]{2,100}") {
+            prop_assert_eq!(max_subsequence_number(&line, 2), Some(max_joltage(&line) as u128));
+        }
+
+        #[test]
+        fn matches_recursive(line in "[1-9]{12,100}", k in 1usize..=12) {
+            let expected = recursive_joltage(&line, k, 0).2 as u128;
+            prop_assert_eq!(max_subsequence_number(&line, k), Some(expected));
+        }
+    }
+
+    #[test]
+    fn generated_input() {
+        let input = libaoc::testing::generated_input(generate, &grammar(), 100);
+        for line in input.lines() {
+            assert_eq!(line.len(), 100);
+            let expected = recursive_joltage(line, 12, 0).2 as u128;
+            assert_eq!(max_subsequence_number(line, 12), Some(expected));
+        }
+    }
+
+    #[test]
+    fn arbitrary_input_does_not_panic() {
+        libaoc::testing::assert_no_panic(&[try_part_1, try_part_2]);
     }
 }
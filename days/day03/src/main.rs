@@ -1,7 +1,3 @@
-use day03::{part_1, part_2};
+use day03::{grammar, part_1, part_2};
 
-pub fn main() {
-    let year = 2025;
-    let day = 3;
-    libaoc::evaluate(part_1, part_2, year, day);
-}
+libaoc::aoc_main!(part_1, part_2, grammar);
@@ -1,4 +1,10 @@
+use std::collections::VecDeque;
+
 use itertools::Itertools;
+use libaoc::InputError;
+use libaoc::aoc;
+use libaoc::grammar::{Grammar, Section};
+use libaoc::rng::Rng;
 
 #[derive(Debug)]
 struct Grid {
@@ -41,56 +47,161 @@ impl Grid {
             .filter(move |(r, c)| self.at(*r, *c) == b'@' && self.adj(*r, *c) < n)
     }
 
-    fn prune_less_than(&mut self, n: usize) -> usize {
-        let remove = self.less_than(n).collect::<Vec<(usize, usize)>>();
-        let removed = remove.len();
-        for (r, c) in remove {
-            self.remove(r, c);
+    /// Repeatedly remove cells with fewer than `n` neighbors until none remain,
+    /// calling `on_remove` with the round each cell is removed in.
+    ///
+    /// Neighbor counts are computed once up front and decremented as cells are
+    /// removed, so only the neighbors of a removed cell are ever revisited.
+    /// Rounds match removing every qualifying cell at once and rescanning.
+    fn prune_worklist(&mut self, n: usize, mut on_remove: impl FnMut(usize, (usize, usize))) {
+        let stride = self.columns + 1;
+        let mut counts = vec![0; self.cells.len()];
+        let mut queued = vec![false; self.cells.len()];
+        let mut queue = VecDeque::new();
+        for (r, c) in (0..self.rows).cartesian_product(0..self.columns) {
+            if self.at(r, c) == b'@' {
+                let i = stride * r + c;
+                counts[i] = self.adj(r, c);
+                if counts[i] < n {
+                    queued[i] = true;
+                    queue.push_back((r, c, 0));
+                }
+            }
+        }
+        while let Some((row, column, round)) = queue.pop_front() {
+            self.remove(row, column);
+            on_remove(round, (row, column));
+            for &(dr, dc) in &NEIGHBORS {
+                let r = row.wrapping_add_signed(dr);
+                let c = column.wrapping_add_signed(dc);
+                if r < self.rows && c < self.columns && self.at(r, c) == b'@' {
+                    let i = stride * r + c;
+                    counts[i] -= 1;
+                    if counts[i] < n && !queued[i] {
+                        queued[i] = true;
+                        queue.push_back((r, c, round + 1));
+                    }
+                }
+            }
         }
-        removed
     }
 }
 
-impl From<&str> for Grid {
-    fn from(s: &str) -> Self {
+impl TryFrom<&str> for Grid {
+    type Error = InputError;
+
+    /// Every line must be as wide as the first, since cells are indexed by a
+    /// fixed stride.
+    fn try_from(s: &str) -> Result<Self, Self::Error> {
         let columns = s.bytes().position(|b| b == b'\n').unwrap_or(s.len());
-        let rows = (s.len() + 1) / (columns + 1);
-        Self {
+        let mut rows = 0;
+        for (i, line) in s.split_terminator('\n').enumerate() {
+            if line.len() != columns {
+                return Err(InputError::malformed(
+                    i + 1,
+                    format!("expected {columns} cells, found {}", line.len()),
+                ));
+            }
+            rows += 1;
+        }
+        Ok(Self {
             rows,
             columns,
             cells: s.as_bytes().to_vec(),
-        }
+        })
     }
 }
 
+/// A rectangular grid of paper rolls.
+#[aoc(grammar)]
+pub fn grammar() -> Grammar {
+    Section::lines("[.@]+").uniform_width().into()
+}
+
+#[aoc(part = 1)]
+pub fn try_part_1(input: &str) -> Result<usize, InputError> {
+    let grid = Grid::try_from(input)?;
+    Ok(grid.less_than(4).count())
+}
+
+#[aoc(part = 2)]
+pub fn try_part_2(input: &str) -> Result<usize, InputError> {
+    let mut grid = Grid::try_from(input)?;
+    let mut total = 0;
+    grid.prune_worklist(4, |_, _| total += 1);
+    Ok(total)
+}
+
 pub fn part_1(input: &str) -> usize {
-    let grid = Grid::from(input);
-    grid.less_than(4).count()
+    try_part_1(input).unwrap_or_else(|e| panic!("{e}"))
 }
 
 pub fn part_2(input: &str) -> usize {
-    let mut grid = Grid::from(input);
-    let mut total = 0;
-    loop {
-        let removed = grid.prune_less_than(4);
-        if removed == 0 {
-            break;
+    try_part_2(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+/// The `(row, column)` of every roll removed in part 2, grouped by the round it
+/// became accessible in.
+pub fn removal_history(input: &str) -> Result<Vec<Vec<(usize, usize)>>, InputError> {
+    let mut grid = Grid::try_from(input)?;
+    let mut rounds: Vec<Vec<(usize, usize)>> = Vec::new();
+    grid.prune_worklist(4, |round, cell| {
+        if rounds.len() == round {
+            rounds.push(Vec::new());
         }
-        total += removed;
-    }
-    total
+        rounds[round].push(cell);
+    });
+    Ok(rounds)
+}
+
+/// A seeded puzzle input: a `size` x `size` grid of paper rolls.
+#[aoc(generator)]
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+    (0..size)
+        .map(|_| {
+            let mut row: String = (0..size)
+                .map(|_| if rng.chance(0.6) { '@' } else { '.' })
+                .collect();
+            row.push('\n');
+            row
+        })
+        .collect()
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+    use libaoc::testing::grid;
+    use proptest::prelude::*;
+
+    /// Reference for part 2: rescan the whole grid after each round.
+    fn prune_less_than(grid: &mut Grid, n: usize) -> Vec<(usize, usize)> {
+        let remove = grid.less_than(n).collect::<Vec<(usize, usize)>>();
+        for &(r, c) in &remove {
+            grid.remove(r, c);
+        }
+        remove
+    }
+
+    const EXAMPLE: &str = "..@@.@@@@.
+@@@.@.@.@@
+@@@@@.@.@@
+@.@@@@..@.
+@@.@@@@.@@
+.@@@@@@@.@
+.@.@.@.@@@
+@.@@@.@@@@
+.@@@@@@@@.
+@.@.@@@.@.";
+
     #[test]
     fn from_str() {
         let input = r"123
 456
 780
 456";
-        let grid = Grid::from(input);
+        let grid = Grid::try_from(input).unwrap();
         assert_eq!(grid.rows, 4);
         assert_eq!(grid.columns, 3);
         assert_eq!(grid.at(0, 0), b'1');
@@ -101,7 +212,7 @@ mod test {
 @26
 @8@
 45@";
-        let grid = Grid::from(input);
+        let grid = Grid::try_from(input).unwrap();
         assert_eq!(grid.adj(2, 1), 4);
         assert_eq!(grid.adj(0, 0), 1);
         assert_eq!(grid.adj(0, 2), 0);
@@ -120,7 +231,7 @@ mod test {
 @.@@@.@@@@
 .@@@@@@@@.
 @.@.@@@.@.";
-        assert_eq!(Grid::from(input).less_than(4).count(), 13);
+        assert_eq!(Grid::try_from(input).unwrap().less_than(4).count(), 13);
     }
     #[test]
     fn example2() {
@@ -138,4 +249,62 @@ mod test {
         let result = part_2(input);
         assert_eq!(result, 43);
     }
+
+    #[test]
+    fn history_matches_rescanning() {
+        let mut grid = Grid::try_from(EXAMPLE).unwrap();
+        let mut expected = Vec::new();
+        loop {
+            let removed = prune_less_than(&mut grid, 4);
+            if removed.is_empty() {
+                break;
+            }
+            expected.push(removed);
+        }
+        let mut history = removal_history(EXAMPLE).unwrap();
+        for round in &mut history {
+            round.sort_unstable();
+        }
+        assert_eq!(history, expected);
+        assert_eq!(history.iter().map(Vec::len).sum::<usize>(), 43);
+    }
+
+    #[test]
+    fn ragged_grid_is_an_error() {
+        assert_eq!(
+            try_part_1("@@@\n@@\n@@@\n"),
+            Err(InputError::malformed(2, "expected 3 cells, found 2"))
+        );
+        assert!(try_part_2("@@\n\n").is_err());
+        assert_eq!(try_part_2(""), Ok(0));
+        assert_eq!(try_part_1("@@\n@@"), Ok(4));
+    }
+
+    proptest! {
+        #[test]
+        fn arbitrary_grids_do_not_panic(input in grid(0..6usize, 0..6usize, b".@\n")) {
+            let _ = (try_part_1(&input), try_part_2(&input));
+        }
+    }
+
+    #[test]
+    fn generated_input() {
+        let input = libaoc::testing::generated_input(generate, &grammar(), 40);
+        let mut grid = Grid::try_from(input.as_str()).unwrap();
+        assert_eq!((grid.rows, grid.columns), (40, 40));
+        let mut expected = 0;
+        loop {
+            let removed = prune_less_than(&mut grid, 4).len();
+            if removed == 0 {
+                break;
+            }
+            expected += removed;
+        }
+        assert_eq!(part_2(&input), expected);
+    }
+
+    #[test]
+    fn arbitrary_input_does_not_panic() {
+        libaoc::testing::assert_no_panic(&[try_part_1, try_part_2]);
+    }
 }
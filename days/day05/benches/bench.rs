@@ -1,11 +1,20 @@
 use criterion::{Criterion, criterion_group, criterion_main};
-use day05::{part_1, part_2};
+use day05::{generate, part_1, part_1_sweep, part_2};
+use libaoc::load_input;
+use libaoc::testing;
 
 fn bench(c: &mut Criterion) {
-    let input = include_str!("../.input/2025/5.txt");
-    c.bench_function("part1", |b| b.iter(|| part_1(input)));
-    c.bench_function("part2", |b| b.iter(|| part_2(input)));
+    let puzzle = libaoc::puzzle!();
+    let input = load_input(puzzle.year, puzzle.day);
+    c.bench_function("part_1", |b| b.iter(|| part_1(&input)));
+    c.bench_function("part_1_sweep", |b| b.iter(|| part_1_sweep(&input)));
+    c.bench_function("part_2", |b| b.iter(|| part_2(&input)));
 }
 
-criterion_group!(benches, bench);
+/// Solve generated inputs of increasing size to see how each part scales.
+fn scaling(c: &mut Criterion) {
+    testing::scaling(c, generate, &[100, 1_000, 10_000], [part_1, part_2]);
+}
+
+criterion_group!(benches, scaling, bench);
 criterion_main!(benches);
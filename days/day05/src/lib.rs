@@ -1,3 +1,9 @@
+use std::ops::RangeInclusive;
+
+use libaoc::InputError;
+use libaoc::aoc;
+use libaoc::grammar::{Grammar, Section};
+use libaoc::rng::Rng;
 
 #[derive(Clone, Copy, Debug, Default, PartialEq, Eq, PartialOrd, Ord)]
 struct Range {
@@ -16,8 +22,9 @@ impl Range {
         self.l = self.l.min(other.l);
         self.r = self.r.max(other.r);
     }
-    fn size(&self) -> usize {
-        self.r - self.l + 1
+    /// The number of IDs in the range, or `None` if that overflows a usize.
+    fn size(&self) -> Option<usize> {
+        (self.r - self.l).checked_add(1)
     }
 }
 
@@ -25,12 +32,43 @@ impl Range {
 struct RangeSet(Vec<Range>);
 
 impl RangeSet {
-    fn add(mut self, new_range: Range) -> Self {
-        self.0.push(new_range);
-        self.0.sort_unstable();
-        let mut consolidated: Vec<Range> = Vec::with_capacity(self.0.len());
+    fn size(&self) -> Option<usize> {
+        self.0
+            .iter()
+            .try_fold(0usize, |total, range| total.checked_add(range.size()?))
+    }
+    /// The merged range containing `value`, found by binary search.
+    fn find(&self, value: usize) -> Option<&Range> {
+        let i = self.0.partition_point(|range| range.r < value);
+        self.0.get(i).filter(|range| range.l <= value)
+    }
+    fn contains(&self, value: usize) -> bool {
+        self.find(value).is_some()
+    }
+    /// Count the `values` inside the set by walking both in order. `values`
+    /// must be sorted.
+    fn count_sorted(&self, values: &[usize]) -> usize {
+        let mut ranges = self.0.iter().peekable();
+        let mut count = 0;
+        for &value in values {
+            while ranges.next_if(|range| range.r < value).is_some() {}
+            match ranges.peek() {
+                Some(range) if range.l <= value => count += 1,
+                Some(_) => {}
+                None => break,
+            }
+        }
+        count
+    }
+}
 
-        for range in self.0 {
+impl FromIterator<Range> for RangeSet {
+    /// Sort once, then merge overlapping and adjacent ranges.
+    fn from_iter<T: IntoIterator<Item = Range>>(iter: T) -> Self {
+        let mut ranges: Vec<Range> = iter.into_iter().collect();
+        ranges.sort_unstable();
+        let mut consolidated: Vec<Range> = Vec::with_capacity(ranges.len());
+        for range in ranges {
             match consolidated.last_mut() {
                 Some(last) if last.is_overlap(range) => last.expand(range),
                 _ => consolidated.push(range),
@@ -38,64 +76,144 @@ impl RangeSet {
         }
         Self(consolidated)
     }
-    fn size(&self) -> usize {
-        self.0.iter().map(Range::size).sum()
-    }
 }
 
-impl From<(&str, &str)> for Range {
-    fn from(value: (&str, &str)) -> Self {
-        Self {
-            l: value
-                .0
-                .parse()
-                .unwrap_or_else(|_| panic!("Unable to parse value: {}", value.0)),
-            r: value
-                .1
-                .parse()
-                .unwrap_or_else(|_| panic!("Unable to parse value: {}", value.1)),
+impl TryFrom<&str> for Range {
+    type Error = String;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        let (l, r) = value
+            .split_once('-')
+            .ok_or_else(|| format!("unable to split range from {value:?}"))?;
+        let parse = |s: &str| {
+            s.parse()
+                .map_err(|_| format!("unable to parse value {s:?}"))
+        };
+        let (l, r) = (parse(l)?, parse(r)?);
+        if l > r {
+            return Err(format!("range {value:?} ends before it starts"));
         }
+        Ok(Self { l, r })
     }
 }
 
-impl From<&str> for Range {
-    fn from(value: &str) -> Self {
-        let range = value
-            .split_once('-')
-            .unwrap_or_else(|| panic!("Unable to split range from {value}"));
-        Self::from(range)
-    }
+/// Split the input at the blank line, returning the range section, the ID
+/// section, and the line number the ID section starts on.
+fn sections(input: &str) -> Result<(&str, &str, usize), InputError> {
+    let (top, bottom) = input.split_once("\n\n").ok_or_else(|| {
+        InputError::malformed(
+            input.lines().count() + 1,
+            "missing blank line between ranges and IDs",
+        )
+    })?;
+    Ok((top, bottom, top.lines().count() + 2))
+}
+
+fn parse_ranges(top: &str) -> impl Iterator<Item = Result<Range, InputError>> + '_ {
+    top.lines()
+        .enumerate()
+        .map(|(i, line)| Range::try_from(line).map_err(|e| InputError::malformed(i + 1, e)))
+}
+
+fn parse(input: &str) -> Result<(RangeSet, Vec<usize>), InputError> {
+    let (top, bottom, first_id_line) = sections(input)?;
+    let ranges = parse_ranges(top).collect::<Result<_, _>>()?;
+    let ids = bottom
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            line.parse().map_err(|_| {
+                InputError::malformed(first_id_line + i, format!("invalid ID {line:?}"))
+            })
+        })
+        .collect::<Result<_, _>>()?;
+    Ok((ranges, ids))
+}
+
+/// Fresh ID ranges, a blank line, then ingredient IDs.
+#[aoc(grammar)]
+pub fn grammar() -> Grammar {
+    Grammar::sections([Section::lines(r"\d+-\d+"), Section::lines(r"\d+")])
+}
+
+#[aoc(part = 1)]
+pub fn try_part_1(input: &str) -> Result<usize, InputError> {
+    let (ranges, ids) = parse(input)?;
+    Ok(ids.into_iter().filter(|&id| ranges.contains(id)).count())
+}
+
+/// Part 1 by sorting the IDs and sweeping them alongside the merged ranges.
+pub fn try_part_1_sweep(input: &str) -> Result<usize, InputError> {
+    let (ranges, mut ids) = parse(input)?;
+    ids.sort_unstable();
+    Ok(ranges.count_sorted(&ids))
+}
+
+/// An ingredient ID and the merged fresh range it falls in, if any.
+pub type Location = (usize, Option<RangeInclusive<usize>>);
+
+/// Each ingredient ID paired with the merged fresh range it falls in, if any.
+pub fn locate_ids(input: &str) -> Result<Vec<Location>, InputError> {
+    let (ranges, ids) = parse(input)?;
+    Ok(ids
+        .into_iter()
+        .map(|id| (id, ranges.find(id).map(|range| range.l..=range.r)))
+        .collect())
+}
+
+#[aoc(part = 2)]
+pub fn try_part_2(input: &str) -> Result<usize, InputError> {
+    let (top, _, _) = sections(input)?;
+    let ranges: RangeSet = parse_ranges(top).collect::<Result<_, _>>()?;
+    ranges
+        .size()
+        .ok_or_else(|| InputError::Unsolvable("fresh ID count overflows a usize".into()))
 }
 
 pub fn part_1(input: &str) -> usize {
-    let (top, bottom) = input.split_once("\n\n").unwrap();
-    let mut top: Vec<_> = top.lines().map(Range::from).collect();
-    top.sort_unstable_by(|a, b| a.l.cmp(&b.l));
-    let bottom: Vec<usize> = bottom.lines().map(|s| s.parse().unwrap()).collect();
-    let mut count = 0;
-    for value in bottom {
-        for range in &top {
-            if range.l <= value && value <= range.r {
-                count += 1;
-                break;
-            }
-        }
-    }
-    count
+    try_part_1(input).unwrap_or_else(|e| panic!("{e}"))
 }
 
 pub fn part_2(input: &str) -> usize {
-    let (top, _) = input.split_once("\n\n").unwrap();
-    let ranges = top
-        .lines()
-        .map(Range::from)
-        .fold(RangeSet::default(), RangeSet::add);
-    ranges.size()
+    try_part_2(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn part_1_sweep(input: &str) -> usize {
+    try_part_1_sweep(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+/// A seeded puzzle input of `size` fresh ID ranges and `5 * size` IDs.
+#[aoc(generator)]
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+    let ranges: Vec<(usize, usize)> = (0..size)
+        .map(|_| {
+            let l = rng.range(1..100_000_000_000_000);
+            (l, l + rng.range(0..1_000_000_000_000))
+        })
+        .collect();
+    let mut input = String::new();
+    for (l, r) in &ranges {
+        input += &format!("{l}-{r}\n");
+    }
+    input.push('\n');
+    for _ in 0..5 * size {
+        // Roughly half the IDs land inside some range.
+        let id = if ranges.is_empty() || rng.chance(0.5) {
+            rng.range(1..100_000_000_000_000)
+        } else {
+            let &(l, r) = rng.pick(&ranges);
+            rng.range(l..r + 1)
+        };
+        input += &format!("{id}\n");
+    }
+    input
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
+
     #[test]
     fn example1() {
         let input = "3-5
@@ -113,6 +231,34 @@ mod test {
         assert_eq!(part_1(input), 3);
     }
 
+    #[test]
+    fn example1_sweep() {
+        let input = "3-5
+10-14
+16-20
+12-18
+
+1
+5
+8
+11
+17
+32
+";
+        assert_eq!(try_part_1_sweep(input), Ok(3));
+        assert_eq!(
+            locate_ids(input).unwrap(),
+            [
+                (1, None),
+                (5, Some(3..=5)),
+                (8, None),
+                (11, Some(10..=20)),
+                (17, Some(10..=20)),
+                (32, None),
+            ]
+        );
+    }
+
     #[test]
     fn example2() {
         let input = "3-5
@@ -128,5 +274,67 @@ mod test {
 32
 ";
         assert_eq!(part_2(input), 14);
+        assert_eq!(grammar().validate(input), Ok(()));
+        assert!(grammar().validate(&input.replace('\n', "\r\n")).is_err());
+    }
+
+    #[test]
+    fn lookups_match_linear_scan() {
+        let ranges = [(3, 5), (10, 14), (16, 20), (12, 18), (22, 22), (40, 45)];
+        let set: RangeSet = ranges.iter().map(|&(l, r)| Range { l, r }).collect();
+        let merged = [(3, 5), (10, 20), (22, 22), (40, 45)].map(|(l, r)| Range { l, r });
+        assert_eq!(set.0, merged);
+        let values: Vec<usize> = (0..50).collect();
+        for &value in &values {
+            let linear = ranges.iter().any(|&(l, r)| l <= value && value <= r);
+            assert_eq!(set.contains(value), linear, "value = {value}");
+        }
+        let expected = values.iter().filter(|&&v| set.contains(v)).count();
+        assert_eq!(set.count_sorted(&values), expected);
+    }
+
+    #[test]
+    fn generated_input() {
+        let input = libaoc::testing::generated_input(generate, &grammar(), 100);
+        let (top, bottom) = input.split_once("\n\n").unwrap();
+        assert_eq!((top.lines().count(), bottom.lines().count()), (100, 500));
+        let ranges: Vec<Range> = parse_ranges(top).map(Result::unwrap).collect();
+        let expected = bottom
+            .lines()
+            .map(|id| id.parse::<usize>().unwrap())
+            .filter(|&id| ranges.iter().any(|r| r.l <= id && id <= r.r))
+            .count();
+        assert_eq!(part_1(&input), expected);
+        assert_eq!(try_part_1_sweep(&input), Ok(expected));
+    }
+
+    #[test]
+    fn invalid_input_is_an_error() {
+        let cases = [
+            ("3-5\n10-14\n", 3),
+            ("3-5\nx-14\n\n4\n", 2),
+            ("3-5\n14-10\n\n4\n", 2),
+            ("3-5\n\n4\n-1\n", 4),
+            ("3-5\n\n4\n\n", 4),
+        ];
+        for (input, line) in cases {
+            assert!(
+                matches!(try_part_1(input), Err(InputError::Malformed { line: l, .. }) if l == line),
+                "{input:?}: {:?}",
+                try_part_1(input)
+            );
+        }
+        assert_eq!(
+            try_part_2("0-18446744073709551615\n\n"),
+            Err(InputError::Unsolvable(
+                "fresh ID count overflows a usize".into()
+            ))
+        );
+        assert_eq!(try_part_2("1-18446744073709551615\n\n"), Ok(usize::MAX));
+    }
+
+    #[test]
+    fn arbitrary_input_does_not_panic() {
+        libaoc::testing::assert_no_panic(&[try_part_1, try_part_1_sweep, try_part_2]);
     }
 }
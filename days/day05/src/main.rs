@@ -1,8 +1,3 @@
-#![allow(dead_code)]
-use day05::{part_1, part_2};
+use day05::{grammar, part_1, part_2};
 
-pub fn main() {
-    let year = 2025;
-    let day = 5;
-    libaoc::evaluate(part_1, part_2, year, day);
-}
+libaoc::aoc_main!(part_1, part_2, grammar);
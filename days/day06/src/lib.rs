@@ -1,133 +1,368 @@
-#[derive(Debug, Default, Clone)]
-enum Op {
+use libaoc::InputError;
+use libaoc::aoc;
+use libaoc::grammar::{Grammar, Section};
+use libaoc::rng::Rng;
+use num_bigint::BigInt;
+use thiserror::Error;
+
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub enum Op {
     #[default]
     Add,
     Mul,
+    Sub,
+    Div,
+}
+
+/// A symbol that is not one of the worksheet operators.
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+#[error("{0:?} is not one of +, *, - or /")]
+pub struct InvalidOp(pub String);
+
+impl TryFrom<&str> for Op {
+    type Error = InvalidOp;
+
+    fn try_from(value: &str) -> Result<Self, Self::Error> {
+        match value.as_bytes() {
+            &[b] => Op::try_from(b),
+            _ => Err(InvalidOp(value.into())),
+        }
+    }
 }
-impl From<&str> for Op {
-    fn from(value: &str) -> Self {
+
+impl TryFrom<u8> for Op {
+    type Error = InvalidOp;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
         match value {
-            "+" => Op::Add,
-            "*" => Op::Mul,
-            _ => panic!("Got a non [+ or *] op symbol."),
+            b'+' => Ok(Op::Add),
+            b'*' => Ok(Op::Mul),
+            b'-' => Ok(Op::Sub),
+            b'/' => Ok(Op::Div),
+            _ => Err(InvalidOp(value.escape_ascii().to_string())),
         }
     }
 }
 
-#[derive(Debug, Clone, Default)]
-struct Grid {
-    grid: Vec<Vec<char>>,
+/// Why a single problem could not be evaluated.
+#[derive(Debug, Error, Clone, Copy, PartialEq, Eq)]
+pub enum ArithmeticError {
+    #[error("result does not fit in the chosen number type")]
+    Overflow,
+    #[error("division by zero")]
+    DivideByZero,
+    #[error("{0:?} needs at least one number")]
+    NoOperands(Op),
+}
+
+#[derive(Debug, Error, Clone, PartialEq, Eq)]
+pub enum WorksheetError {
+    #[error(transparent)]
+    Input(#[from] InputError),
+    #[error("problem {index} failed: {source}")]
+    Problem {
+        index: usize,
+        source: ArithmeticError,
+    },
+    #[error("grand total does not fit in the chosen number type")]
+    TotalOverflow,
 }
 
-impl Grid {
-    fn w(&self) -> usize {
-        self.grid[0].len()
+/// A number type worksheets can be evaluated in, e.g. `usize`, `u128`, or
+/// `BigInt` for arbitrary precision.
+pub trait Value: Sized + Clone {
+    fn from_usize(n: usize) -> Self;
+    /// Read ASCII decimal digits, or `None` if the number is not
+    /// representable.
+    fn from_digits(digits: &[u8]) -> Option<Self> {
+        digits.iter().try_fold(Self::from_usize(0), |n, &d| {
+            n.apply(Op::Mul, Self::from_usize(10))?
+                .apply(Op::Add, Self::from_usize(usize::from(d - b'0')))
+        })
     }
-    fn h(&self) -> usize {
-        self.grid.len()
+    fn is_zero(&self) -> bool;
+    /// `self op rhs`, or `None` if the result is not representable.
+    fn apply(self, op: Op, rhs: Self) -> Option<Self>;
+}
+
+macro_rules! checked_value {
+    ($($t:ty),*) => {$(
+        impl Value for $t {
+            fn from_usize(n: usize) -> Self {
+                n as $t
+            }
+            fn is_zero(&self) -> bool {
+                *self == 0
+            }
+            fn apply(self, op: Op, rhs: Self) -> Option<Self> {
+                match op {
+                    Op::Add => self.checked_add(rhs),
+                    Op::Mul => self.checked_mul(rhs),
+                    Op::Sub => self.checked_sub(rhs),
+                    Op::Div => self.checked_div(rhs),
+                }
+            }
+        }
+    )*};
+}
+
+checked_value!(usize, u128);
+
+impl Value for BigInt {
+    fn from_usize(n: usize) -> Self {
+        BigInt::from(n)
+    }
+    fn is_zero(&self) -> bool {
+        *self == BigInt::ZERO
     }
-    fn at(&self, r: usize, c: usize) -> char {
-        self.grid[r][c]
+    fn apply(self, op: Op, rhs: Self) -> Option<Self> {
+        Some(match op {
+            Op::Add => self + rhs,
+            Op::Mul => self * rhs,
+            Op::Sub => self - rhs,
+            Op::Div => self / rhs,
+        })
+    }
+}
+
+/// How the digits of a problem's block are read into numbers.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Reading {
+    /// Each line of the block is one number (part 1).
+    Rows,
+    /// Each column of the block is one number, digits top to bottom, with the
+    /// rightmost column first (part 2).
+    Columns,
+}
+
+/// One worksheet problem: its operands, read as `T`, and the operator applied
+/// to them.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Problem<T = usize> {
+    pub op: Op,
+    pub numbers: Vec<T>,
+}
+
+impl<T: Value> Problem<T> {
+    /// Apply the operator left to right across the numbers.
+    ///
+    /// # Errors
+    /// If an intermediate result is not representable in `T`, a division is by
+    /// zero, or a `-`/`/` problem has no numbers.
+    pub fn evaluate(&self) -> Result<T, ArithmeticError> {
+        let mut acc = Accumulator::from(self.op);
+        for n in &self.numbers {
+            acc.acc(n.clone())?;
+        }
+        acc.finish()
     }
 }
 
-impl<'a> FromIterator<&'a str> for Grid {
-    fn from_iter<T: IntoIterator<Item = &'a str>>(iter: T) -> Self {
-        let grid = iter.into_iter().map(|s| s.chars().collect()).collect();
-        Self { grid }
+/// Sum of every problem's result, evaluated in type `T`.
+///
+/// # Errors
+/// Reports why the worksheet could not be parsed, the index of the first
+/// problem that fails to evaluate, or that the sum itself overflowed.
+pub fn grand_total<T: Value>(input: &str, reading: Reading) -> Result<T, WorksheetError> {
+    problems::<T>(input, reading)?.iter().enumerate().try_fold(
+        T::from_usize(0),
+        |total, (index, problem)| {
+            let value = problem
+                .evaluate()
+                .map_err(|source| WorksheetError::Problem { index, source })?;
+            total
+                .apply(Op::Add, value)
+                .ok_or(WorksheetError::TotalOverflow)
+        },
+    )
+}
+
+/// Split a worksheet into its problems, reading the numbers as `T`.
+///
+/// The last non-empty line holds the operators and every line above it holds
+/// digits. Problems are separated by columns that are blank on every line.
+/// Lines may have different lengths; missing bytes are treated as blank.
+///
+/// # Errors
+/// If a digit line holds anything but digits and spaces, the operator line
+/// holds anything but operators and spaces, a problem has no operator, or a
+/// number is not representable in `T`.
+pub fn problems<T: Value>(input: &str, reading: Reading) -> Result<Vec<Problem<T>>, InputError> {
+    let mut lines: Vec<&[u8]> = input.lines().map(str::as_bytes).collect();
+    while lines.last().is_some_and(|l| l.trim_ascii().is_empty()) {
+        lines.pop();
     }
+    let Some(ops) = lines.pop() else {
+        return Ok(Vec::new());
+    };
+    let ops_line = lines.len() + 1;
+    for (i, line) in lines.iter().enumerate() {
+        if let Some(b) = line.iter().find(|&&b| b != b' ' && !b.is_ascii_digit()) {
+            return Err(InputError::malformed(
+                i + 1,
+                format!("expected digits, found {:?}", b.escape_ascii().to_string()),
+            ));
+        }
+    }
+    for &b in ops.iter().filter(|&&b| b != b' ') {
+        Op::try_from(b).map_err(|e| InputError::malformed(ops_line, e.to_string()))?;
+    }
+    let width = lines
+        .iter()
+        .chain([&ops])
+        .map(|l| l.len())
+        .max()
+        .unwrap_or(0);
+    let at = |line: &[u8], c: usize| line.get(c).copied().unwrap_or(b' ');
+    let blank = |c: usize| at(ops, c) == b' ' && lines.iter().all(|l| at(l, c) == b' ');
+
+    let mut problems = Vec::new();
+    let mut c = 0;
+    while c < width {
+        if blank(c) {
+            c += 1;
+            continue;
+        }
+        let start = c;
+        while c < width && !blank(c) {
+            c += 1;
+        }
+        let op = (start..c)
+            .map(|c| at(ops, c))
+            .find(|&b| b != b' ')
+            .and_then(|b| Op::try_from(b).ok())
+            .ok_or_else(|| {
+                InputError::malformed(ops_line, format!("no operator under columns {start}..{c}"))
+            })?;
+        let numbers: Option<Vec<_>> = match reading {
+            Reading::Rows => lines
+                .iter()
+                .filter_map(|l| read_number((start..c).map(|c| at(l, c))))
+                .collect(),
+            Reading::Columns => (start..c)
+                .rev()
+                .filter_map(|c| read_number(lines.iter().map(|l| at(l, c))))
+                .collect(),
+        };
+        let numbers = numbers.ok_or_else(|| {
+            InputError::Unsolvable(format!(
+                "a number in columns {start}..{c} does not fit in the chosen number type"
+            ))
+        })?;
+        problems.push(Problem { op, numbers });
+    }
+    Ok(problems)
 }
 
+/// Read the digits from a run of digits and blanks, ignoring blanks. `None` if
+/// all blank, `Some(None)` if the number is not representable in `T`.
+fn read_number<T: Value>(bytes: impl Iterator<Item = u8>) -> Option<Option<T>> {
+    let digits: Vec<u8> = bytes.filter(|&b| b != b' ').collect();
+    (!digits.is_empty()).then(|| T::from_digits(&digits))
+}
+
+/// Folds numbers into a running result, seeded with the first number. With no
+/// numbers, `+` and `*` give their identity and `-` and `/` are an error.
 #[derive(Debug, Clone)]
-struct Accumulator {
+struct Accumulator<T> {
     op: Op,
-    val: usize,
+    val: Option<T>,
 }
 
-impl Accumulator {
-    fn acc(&mut self, val: usize) {
-        self.val = match self.op {
-            Op::Add => self.val + val,
-            Op::Mul => self.val * val,
+impl<T: Value> Accumulator<T> {
+    fn acc(&mut self, val: T) -> Result<(), ArithmeticError> {
+        self.val = Some(match self.val.take() {
+            None => val,
+            Some(_) if self.op == Op::Div && val.is_zero() => {
+                return Err(ArithmeticError::DivideByZero);
+            }
+            Some(acc) => acc.apply(self.op, val).ok_or(ArithmeticError::Overflow)?,
+        });
+        Ok(())
+    }
+    fn finish(self) -> Result<T, ArithmeticError> {
+        match (self.val, self.op) {
+            (Some(val), _) => Ok(val),
+            (None, Op::Add) => Ok(T::from_usize(0)),
+            (None, Op::Mul) => Ok(T::from_usize(1)),
+            (None, op) => Err(ArithmeticError::NoOperands(op)),
         }
     }
 }
 
-impl From<Op> for Accumulator {
+impl<T> From<Op> for Accumulator<T> {
     fn from(op: Op) -> Self {
-        let val = match op {
-            Op::Add => 0,
-            Op::Mul => 1,
-        };
-        Accumulator { op, val }
+        Accumulator { op, val: None }
     }
 }
 
+fn solve(input: &str, reading: Reading) -> Result<usize, InputError> {
+    grand_total(input, reading).map_err(|e| match e {
+        WorksheetError::Input(e) => e,
+        e => InputError::Unsolvable(e.to_string()),
+    })
+}
+
+/// Rows of numbers over an operator row. Lines may be ragged.
+#[aoc(grammar)]
+pub fn grammar() -> Grammar {
+    Section::lines(r"[\d ]+").ending_with(r"[-+*/ ]+").into()
+}
+
+#[aoc(part = 1)]
+pub fn try_part_1(input: &str) -> Result<usize, InputError> {
+    solve(input, Reading::Rows)
+}
+
+#[aoc(part = 2)]
+pub fn try_part_2(input: &str) -> Result<usize, InputError> {
+    solve(input, Reading::Columns)
+}
+
 pub fn part_1(input: &str) -> usize {
-    let mut line_strs = input.lines().map(|s| s.split_whitespace()).rev();
-    let mut accs = line_strs
-        .next()
-        .unwrap()
-        .map(Op::from)
-        .map(Accumulator::from)
-        .collect::<Vec<_>>();
-    let line_values = line_strs.map(|l| l.map(|s| s.parse::<usize>().unwrap()));
-    for line in line_values {
-        for (acc, val) in accs.iter_mut().zip(line) {
-            acc.acc(val);
-        }
-    }
-    accs.iter().map(|a| a.val).sum()
+    try_part_1(input).unwrap_or_else(|e| panic!("{e}"))
 }
 
 pub fn part_2(input: &str) -> usize {
-    let grid: Grid = input.lines().collect();
-    let w = grid.w();
-    let h = grid.h();
-    let mut total = 0;
-    let mut numbers = Vec::new();
-    let mut op_idx = 0;
-    let mut op_complete = false;
-    for column in (0..w).rev() {
-        if op_complete {
-            op_complete = false;
-            op_idx = 0;
-            numbers.clear();
-            continue;
-        }
-        numbers.push(0);
-
-        for row in 0..h {
-            // dbg!((column, row, op_idx));
-            match grid.at(row, column) {
-                c if c.is_ascii_digit() => {
-                    numbers[op_idx] = numbers[op_idx] * 10 + c.to_digit(10).unwrap() as usize;
-                    // dbg!(&numbers, op_idx, c);
-                }
-                ' ' if row == h - 1 => op_idx += 1,
-                '+' => {
-                    let value = numbers.iter().sum::<usize>();
-                    // dbg!('+', &numbers, value);
-                    total += value;
-                    op_complete = true
-                }
-                '*' => {
-                    let value = numbers.iter().take(h - 1).product::<usize>();
-                    // dbg!('*', &numbers, value);
-                    total += value;
-                    op_complete = true
-                }
-                _ => {}
+    try_part_2(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+/// A seeded puzzle input of `size` problems, each with four numbers of up to
+/// four digits aligned to one side of their column block.
+#[aoc(generator)]
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+    let mut lines = vec![String::new(); 5];
+    for i in 0..size {
+        let numbers: Vec<String> = (0..4)
+            .map(|_| {
+                let digits = rng.range(1..5) as u32;
+                rng.range(10usize.pow(digits - 1)..10usize.pow(digits))
+                    .to_string()
+            })
+            .collect();
+        let width = numbers.iter().map(String::len).max().unwrap_or(0);
+        let left = rng.chance(0.5);
+        let separator = if i == 0 { "" } else { " " };
+        for (line, number) in lines.iter_mut().zip(&numbers) {
+            line.push_str(separator);
+            if left {
+                line.push_str(&format!("{number:<width$}"));
+            } else {
+                line.push_str(&format!("{number:>width$}"));
             }
         }
+        lines[4].push_str(separator);
+        lines[4].push_str(&format!("{:<width$}", rng.pick(&['+', '*'])));
     }
-    total
+    lines.join("\n") + "\n"
 }
 
 #[cfg(test)]
 mod test {
     use super::*;
     use indoc::indoc;
+    use proptest::prelude::*;
     #[test]
     fn example1() {
         let input = indoc! {"
@@ -146,4 +381,164 @@ mod test {
 *   +   *   +  "};
         assert_eq!(part_2(input), 3263827);
     }
+    #[test]
+    fn parsed_problems() {
+        let input = indoc! {"
+123 328  51 64 
+ 45 64  387 23 
+  6 98  215 314
+*   +   *   +  "};
+        let rows = problems::<usize>(input, Reading::Rows).unwrap();
+        assert_eq!(rows.len(), 4);
+        assert_eq!(
+            rows[0],
+            Problem {
+                op: Op::Mul,
+                numbers: vec![123, 45, 6]
+            }
+        );
+        let columns = problems::<usize>(input, Reading::Columns).unwrap();
+        assert_eq!(
+            columns[3],
+            Problem {
+                op: Op::Add,
+                numbers: vec![4, 431, 623]
+            }
+        );
+    }
+    #[test]
+    fn ragged_lines() {
+        // Same worksheet with trailing spaces stripped, so lines differ in width.
+        let input = "123 328  51 64\n 45 64  387 23\n  6 98  215 314\n*   +   *   +\n";
+        assert_eq!(part_1(input), 4277556);
+        assert_eq!(part_2(input), 3263827);
+    }
+    #[test]
+    fn trailing_spaces() {
+        let input =
+            "123 328  51 64      \n 45 64  387 23  \n  6 98  215 314   \n*   +   *   +     \n\n";
+        assert_eq!(part_1(input), 4277556);
+        assert_eq!(part_2(input), 3263827);
+        assert_eq!(problems::<usize>(input, Reading::Columns).unwrap().len(), 4);
+    }
+    #[test]
+    fn overflow_is_reported() {
+        let input = "1 99999999999\n2 99999999999\n+ *\n";
+        assert_eq!(
+            grand_total::<usize>(input, Reading::Rows),
+            Err(WorksheetError::Problem {
+                index: 1,
+                source: ArithmeticError::Overflow
+            })
+        );
+    }
+    #[test]
+    fn wider_types() {
+        let big = "9999999999";
+        let input = format!("{big}\n{big}\n{big}\n*\n");
+        assert_eq!(
+            grand_total::<usize>(&input, Reading::Rows),
+            Err(WorksheetError::Problem {
+                index: 0,
+                source: ArithmeticError::Overflow
+            })
+        );
+        let n: u128 = big.parse().unwrap();
+        assert_eq!(grand_total::<u128>(&input, Reading::Rows), Ok(n * n * n));
+        let n: BigInt = big.parse().unwrap();
+        assert_eq!(
+            grand_total::<BigInt>(&input, Reading::Rows),
+            Ok(&n * &n * &n)
+        );
+        let n = BigInt::from(10u8).pow(60);
+        let input = format!("{0}\n{0}\n{0}\n{0}\n*\n", "1000000000000000");
+        assert_eq!(grand_total::<BigInt>(&input, Reading::Rows), Ok(n));
+    }
+    #[test]
+    fn subtraction_and_division() {
+        let input = indoc! {"
+            100 100 7
+             30  20 0
+              5   5 1
+            -   /   /"};
+        let totals: Vec<_> = problems::<usize>(input, Reading::Rows)
+            .unwrap()
+            .iter()
+            .map(Problem::evaluate)
+            .collect();
+        assert_eq!(totals, [Ok(65), Ok(1), Err(ArithmeticError::DivideByZero)]);
+        let underflow = "5\n6\n-\n";
+        assert_eq!(
+            grand_total::<usize>(underflow, Reading::Rows),
+            Err(WorksheetError::Problem {
+                index: 0,
+                source: ArithmeticError::Overflow
+            })
+        );
+        assert_eq!(
+            grand_total::<BigInt>(underflow, Reading::Rows),
+            Ok(BigInt::from(-1))
+        );
+    }
+
+    #[test]
+    fn malformed_worksheets() {
+        let cases = [
+            (
+                "12 3\n4x 5\n+  *\n",
+                InputError::malformed(2, "expected digits, found \"x\""),
+            ),
+            (
+                "12 3\n4  5\n+  %\n",
+                InputError::malformed(3, "\"%\" is not one of +, *, - or /"),
+            ),
+            (
+                "12 3\n4  5\n+\n",
+                InputError::malformed(3, "no operator under columns 3..4"),
+            ),
+        ];
+        for (input, error) in cases {
+            assert_eq!(try_part_1(input), Err(error.clone()));
+            assert_eq!(try_part_2(input), Err(error));
+        }
+        let huge = format!("{}\n+\n", "9".repeat(30));
+        assert!(matches!(try_part_1(&huge), Err(InputError::Unsolvable(_))));
+        assert_eq!(try_part_2(&huge), Ok(9 * 30));
+        let n: u128 = "9".repeat(30).parse().unwrap();
+        assert_eq!(grand_total::<u128>(&huge, Reading::Rows), Ok(n));
+        let n: BigInt = "9".repeat(30).parse().unwrap();
+        assert_eq!(grand_total::<BigInt>(&huge, Reading::Rows), Ok(n));
+        assert!(matches!(
+            try_part_1("5\n6\n-\n"),
+            Err(InputError::Unsolvable(_))
+        ));
+        assert_eq!(Op::try_from("*"), Ok(Op::Mul));
+        assert_eq!(Op::try_from("**"), Err(InvalidOp("**".into())));
+    }
+
+    proptest! {
+        #[test]
+        fn arbitrary_worksheets_do_not_panic(input in "[0-9 ]{0,12}(\n[0-9 ]{0,12}){0,3}\n[-+*/ ]{0,12}") {
+            let _ = (try_part_1(&input), try_part_2(&input));
+        }
+    }
+
+    #[test]
+    fn generated_input() {
+        let input = libaoc::testing::generated_input(generate, &grammar(), 100);
+        assert_eq!(problems::<usize>(&input, Reading::Rows).unwrap().len(), 100);
+        assert!(
+            problems::<usize>(&input, Reading::Columns)
+                .unwrap()
+                .iter()
+                .all(|p| (1..=4).contains(&p.numbers.len()))
+        );
+        part_1(&input);
+        part_2(&input);
+    }
+
+    #[test]
+    fn arbitrary_input_does_not_panic() {
+        libaoc::testing::assert_no_panic(&[try_part_1, try_part_2]);
+    }
 }
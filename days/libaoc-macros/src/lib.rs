@@ -0,0 +1,143 @@
+//! The `#[aoc(...)]` attribute, re-exported as `libaoc::aoc`. See
+//! `libaoc::registry` for what it registers.
+use proc_macro::TokenStream;
+use proc_macro2::Span;
+use quote::quote;
+use syn::parse::{Parse, ParseStream};
+use syn::punctuated::Punctuated;
+use syn::{Error, Expr, Ident, ItemFn, LitInt, Stmt, Token, parse_macro_input};
+
+/// What the function is: a part's solver, the input grammar, or the input
+/// generator.
+enum Kind {
+    Part(u8),
+    Grammar,
+    Generator,
+}
+
+struct Args {
+    year: Option<u16>,
+    day: Option<u8>,
+    kind: Kind,
+}
+
+/// One `key = value` or bare `key`.
+struct Arg {
+    key: Ident,
+    value: Option<LitInt>,
+}
+
+impl Parse for Arg {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let key = input.parse()?;
+        let value = if input.peek(Token![=]) {
+            input.parse::<Token![=]>()?;
+            Some(input.parse()?)
+        } else {
+            None
+        };
+        Ok(Self { key, value })
+    }
+}
+
+impl Parse for Args {
+    fn parse(input: ParseStream) -> syn::Result<Self> {
+        let (mut year, mut day, mut kind) = (None, None, None);
+        for arg in Punctuated::<Arg, Token![,]>::parse_terminated(input)? {
+            let name = arg.key.to_string();
+            let value = |arg: &Arg| {
+                arg.value
+                    .clone()
+                    .ok_or_else(|| Error::new(arg.key.span(), format!("expected `{name} = ...`")))
+            };
+            match name.as_str() {
+                "year" => year = Some(value(&arg)?.base10_parse()?),
+                "day" => day = Some(value(&arg)?.base10_parse()?),
+                "part" => {
+                    let literal = value(&arg)?;
+                    match literal.base10_parse()? {
+                        part @ (1 | 2) => kind = Some(Kind::Part(part)),
+                        _ => return Err(Error::new(literal.span(), "part must be 1 or 2")),
+                    }
+                }
+                "grammar" if arg.value.is_none() => kind = Some(Kind::Grammar),
+                "generator" if arg.value.is_none() => kind = Some(Kind::Generator),
+                _ => {
+                    return Err(Error::new(
+                        arg.key.span(),
+                        "expected `year = ..`, `day = ..`, `part = ..`, `grammar` or `generator`",
+                    ));
+                }
+            }
+        }
+        let kind = kind.ok_or_else(|| {
+            Error::new(
+                Span::call_site(),
+                "expected one of `part = 1`, `part = 2`, `grammar` or `generator`",
+            )
+        })?;
+        Ok(Self { year, day, kind })
+    }
+}
+
+/// Whether `function`'s body is just `todo!()`, as copied from the template.
+fn is_todo(function: &ItemFn) -> bool {
+    let mac = match function.block.stmts.as_slice() {
+        [Stmt::Macro(stmt)] => &stmt.mac,
+        [Stmt::Expr(Expr::Macro(expr), _)] => &expr.mac,
+        _ => return false,
+    };
+    mac.path.is_ident("todo")
+}
+
+/// Register a day's function with `libaoc::registry`:
+///
+/// ```ignore
+/// #[aoc(part = 1)]
+/// pub fn try_part_1(input: &str) -> Result<usize, InputError> { .. }
+///
+/// #[aoc(grammar)]
+/// pub fn grammar() -> Grammar { .. }
+///
+/// #[aoc(generator)]
+/// pub fn generate(seed: u64, size: usize) -> String { .. }
+/// ```
+///
+/// The year and day come from the crate's manifest (see `libaoc::manifest`);
+/// `year = ..` and `day = ..` may be given too, and must agree with it.
+///
+/// A function whose body is just `todo!()` registers its day but is not
+/// itself registered, so it is never called.
+#[proc_macro_attribute]
+pub fn aoc(args: TokenStream, item: TokenStream) -> TokenStream {
+    let Args { year, day, kind } = parse_macro_input!(args as Args);
+    let function = parse_macro_input!(item as ItemFn);
+    let name = &function.sig.ident;
+    let todo = is_todo(&function);
+    let option = |value: Option<proc_macro2::TokenStream>| match value {
+        Some(value) => quote!(::core::option::Option::Some(#value)),
+        None => quote!(::core::option::Option::None),
+    };
+    let year = option(year.map(|year| quote!(#year)));
+    let day = option(day.map(|day| quote!(#day)));
+    let kind = match kind {
+        Kind::Part(part) => quote!(::libaoc::registry::Kind::Part(#part, #name)),
+        Kind::Grammar => quote!(::libaoc::registry::Kind::Grammar(#name)),
+        Kind::Generator => quote!(::libaoc::registry::Kind::Generator(#name)),
+    };
+    quote! {
+        #function
+
+        ::libaoc::registry::inventory::submit! {
+            ::libaoc::registry::Entry {
+                name: ::core::concat!(::core::module_path!(), "::", ::core::stringify!(#name)),
+                manifest: ::libaoc::manifest!(),
+                year: #year,
+                day: #day,
+                kind: #kind,
+                todo: #todo,
+            }
+        }
+    }
+    .into()
+}
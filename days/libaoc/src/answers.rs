@@ -0,0 +1,206 @@
+//! Recorded puzzle answers, so every solution can be re-checked against every
+//! input it has been run on.
+//!
+//! Answers live in one TOML file (`.input/answers.toml`) keyed by input, e.g.
+//!
+//! ```toml
+//! ["sam/2025/4"]
+//! part_1 = 1480
+//! part_2 = 8899
+//! ```
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+
+use crate::CACHE_LOCATION;
+
+#[derive(Debug, Error)]
+pub enum AnswersError {
+    #[error("failed to read {path}: {source}")]
+    Read { path: PathBuf, source: io::Error },
+    #[error("invalid answers file {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: toml::de::Error,
+    },
+    #[error("failed to write {path}: {source}")]
+    Write { path: PathBuf, source: io::Error },
+    #[error("failed to serialize answers: {0}")]
+    Serialize(#[from] toml::ser::Error),
+}
+
+/// The known answers for one input.
+#[derive(Clone, Copy, Debug, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub struct Answers {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part_1: Option<usize>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub part_2: Option<usize>,
+}
+
+impl Answers {
+    /// The answer for `part` (1 or 2).
+    #[must_use]
+    pub fn part(&self, part: u8) -> Option<usize> {
+        match part {
+            1 => self.part_1,
+            2 => self.part_2,
+            _ => None,
+        }
+    }
+
+    /// # Panics
+    /// If `part` is not 1 or 2.
+    pub fn set_part(&mut self, part: u8, answer: usize) {
+        match part {
+            1 => self.part_1 = Some(answer),
+            2 => self.part_2 = Some(answer),
+            _ => panic!("there is no part {part}"),
+        }
+    }
+}
+
+/// The key for a profile's cached input; the unnamed `SESSION` profile is
+/// `@session`, which no profile name can spell.
+#[must_use]
+pub fn profile_key(profile: Option<&str>, year: u16, day: u8) -> String {
+    format!("{}/{year}/{day}", profile.unwrap_or("@session"))
+}
+
+/// The key for a day's generated input.
+#[must_use]
+pub fn generated_key(year: u16, day: u8, seed: u64, size: usize) -> String {
+    format!("generated/{year}/{day}/{seed}-{size}")
+}
+
+/// Where answers are recorded.
+#[must_use]
+pub fn answers_path() -> PathBuf {
+    PathBuf::from(format!("{CACHE_LOCATION}/answers.toml"))
+}
+
+/// Answers for every recorded input, by key.
+#[derive(Debug, Default)]
+pub struct AnswerBook {
+    path: PathBuf,
+    entries: BTreeMap<String, Answers>,
+}
+
+impl AnswerBook {
+    /// Read the answers at `path`. A missing file is an empty book.
+    ///
+    /// # Errors
+    /// If the file exists but cannot be read or parsed.
+    pub fn load(path: &Path) -> Result<Self, AnswersError> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(source) => {
+                return Err(AnswersError::Read {
+                    path: path.into(),
+                    source,
+                });
+            }
+        };
+        let entries = toml::from_str(&text).map_err(|source| AnswersError::Parse {
+            path: path.into(),
+            source,
+        })?;
+        Ok(Self {
+            path: path.into(),
+            entries,
+        })
+    }
+
+    /// The recorded answers for `key`; empty if none are recorded.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Answers {
+        self.entries.get(key).copied().unwrap_or_default()
+    }
+
+    /// Record `answer` for `part` of `key`, replacing any earlier answer.
+    pub fn record(&mut self, key: &str, part: u8, answer: usize) {
+        self.entries
+            .entry(key.to_owned())
+            .or_default()
+            .set_part(part, answer);
+    }
+
+    /// Every key with recorded answers, in order.
+    pub fn keys(&self) -> impl Iterator<Item = &str> {
+        self.entries.keys().map(String::as_str)
+    }
+
+    /// Write the book back to the file it was loaded from.
+    ///
+    /// # Errors
+    /// If an answer does not fit in TOML's integers or the file cannot be written.
+    pub fn save(&self) -> Result<(), AnswersError> {
+        let text = toml::to_string(&self.entries)?;
+        if let Some(dir) = self.path.parent() {
+            std::fs::create_dir_all(dir).map_err(|source| AnswersError::Write {
+                path: self.path.clone(),
+                source,
+            })?;
+        }
+        std::fs::write(&self.path, text).map_err(|source| AnswersError::Write {
+            path: self.path.clone(),
+            source,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn keys() {
+        assert_eq!(profile_key(Some("sam"), 2025, 4), "sam/2025/4");
+        assert_eq!(profile_key(None, 2025, 4), "@session/2025/4");
+        assert_ne!(
+            profile_key(Some("session"), 2025, 4),
+            profile_key(None, 2025, 4)
+        );
+        assert_eq!(generated_key(2025, 4, 7, 100), "generated/2025/4/7-100");
+    }
+
+    #[test]
+    fn round_trip() {
+        let path = std::env::temp_dir()
+            .join(format!("libaoc-answers-{}", std::process::id()))
+            .join("answers.toml");
+        let mut book = AnswerBook::load(&path).unwrap();
+        assert_eq!(book.get("sam/2025/4"), Answers::default());
+        book.record("sam/2025/4", 1, 13);
+        book.record("sam/2025/4", 2, 43);
+        book.record("generated/2025/4/0-10", 2, 7);
+        book.record("@session/2025/4", 1, 5);
+        book.save().unwrap();
+
+        let book = AnswerBook::load(&path).unwrap();
+        let answers = book.get("sam/2025/4");
+        assert_eq!((answers.part(1), answers.part(2)), (Some(13), Some(43)));
+        assert_eq!(book.get("generated/2025/4/0-10").part_1, None);
+        assert_eq!(book.get("@session/2025/4").part_1, Some(5));
+        assert_eq!(
+            book.keys().collect::<Vec<_>>(),
+            ["@session/2025/4", "generated/2025/4/0-10", "sam/2025/4"]
+        );
+        std::fs::remove_dir_all(path.parent().unwrap()).unwrap();
+    }
+
+    #[test]
+    fn invalid_file() {
+        let path = std::env::temp_dir().join(format!("libaoc-bad-{}.toml", std::process::id()));
+        std::fs::write(&path, "part_1 = 3").unwrap();
+        assert!(matches!(
+            AnswerBook::load(&path),
+            Err(AnswersError::Parse { .. })
+        ));
+        std::fs::remove_file(path).unwrap();
+    }
+}
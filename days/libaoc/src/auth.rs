@@ -0,0 +1,89 @@
+//! Checking whether a session token is still logged in.
+use std::sync::LazyLock;
+
+use regex::Regex;
+
+use crate::http::{self, Client};
+use crate::session::Session;
+use crate::{AocError, BASE_URI};
+
+/// Who a session token is logged in as.
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum Status {
+    LoggedIn(String),
+    /// The token has expired or was never valid.
+    LoggedOut,
+}
+
+/// Fetch a small page with `session` and read the user name from its header.
+///
+/// # Errors
+/// `AocError::Network` if the page cannot be fetched.
+pub fn status(session: &Session) -> Result<Status, AocError> {
+    status_at(http::client(), BASE_URI, session)
+}
+
+fn status_at(client: &Client, base: &str, session: &Session) -> Result<Status, AocError> {
+    let (code, page) = client.get(&format!("{base}/about"), session)?;
+    if !(200..300).contains(&code) {
+        return Err(AocError::Network(format!("{base}/about returned {code}")));
+    }
+    Ok(user_name(&page).map_or(Status::LoggedOut, Status::LoggedIn))
+}
+
+/// The name in the page header, e.g. `<div class="user">alex <span ...>`.
+/// Logged-out pages have a `[Log In]` link there instead.
+pub(crate) fn user_name(page: &str) -> Option<String> {
+    static USER: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r#"<div class="user">([^<]+)"#).expect("valid regex"));
+    USER.captures(page)
+        .map(|captures| captures[1].trim().to_owned())
+        .filter(|name| !name.is_empty())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::mock;
+
+    const LOGGED_IN: &str =
+        r#"<header><div class="user">alex <span class="star-count">12*</span></div></header>"#;
+    const LOGGED_OUT: &str = r#"<header><div><a href="/auth/login">[Log In]</a></div></header>"#;
+
+    #[test]
+    fn reads_the_user_name() {
+        assert_eq!(user_name(LOGGED_IN).as_deref(), Some("alex"));
+        assert_eq!(
+            user_name(r#"<div class="user">(anonymous user #1234)</div>"#).as_deref(),
+            Some("(anonymous user #1234)")
+        );
+        assert_eq!(user_name(LOGGED_OUT), None);
+    }
+
+    #[test]
+    fn checks_the_session() {
+        let session = Session::new("abc", "test").unwrap();
+        let client = Client::new("libaoc-tests")
+            .lock_file(std::env::temp_dir().join(format!("libaoc-auth-{}", std::process::id())))
+            .min_interval(Duration::ZERO)
+            .retries(0, Duration::ZERO);
+        let (base, server) = mock::serve(vec![(200, LOGGED_IN), (200, LOGGED_OUT), (500, "")]);
+        assert_eq!(
+            status_at(&client, &base, &session).unwrap(),
+            Status::LoggedIn("alex".into())
+        );
+        assert_eq!(
+            status_at(&client, &base, &session).unwrap(),
+            Status::LoggedOut
+        );
+        assert!(matches!(
+            status_at(&client, &base, &session),
+            Err(AocError::Network(_))
+        ));
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /about "));
+        assert!(requests[0].to_lowercase().contains("cookie: session=abc"));
+    }
+}
@@ -0,0 +1,277 @@
+//! Declarative puzzle input formats, checked before solving so that a bad
+//! download is reported by line instead of by a panic deep inside a solver.
+use regex::Regex;
+
+use crate::InputError;
+
+/// A regex that must match a whole line.
+#[derive(Clone, Debug)]
+struct Pattern {
+    source: String,
+    regex: Regex,
+}
+
+impl Pattern {
+    /// # Panics
+    /// If `source` is not a valid regex.
+    fn new(source: &str) -> Self {
+        let regex = Regex::new(&format!("^(?:{source})$"))
+            .unwrap_or_else(|e| panic!("invalid line pattern {source:?}: {e}"));
+        Self {
+            source: source.into(),
+            regex,
+        }
+    }
+
+    /// Why `line` does not match, naming the usual culprits when they apply.
+    fn check(&self, line: &str) -> Result<(), String> {
+        if self.regex.is_match(line) {
+            Ok(())
+        } else if line.ends_with('\r') {
+            Err("line ends with a carriage return (CRLF line endings?)".into())
+        } else if line.trim_end() != line && self.regex.is_match(line.trim_end()) {
+            Err("trailing whitespace".into())
+        } else {
+            Err(format!(
+                "{} does not match `{}`",
+                preview(line),
+                self.source
+            ))
+        }
+    }
+}
+
+/// `line` quoted, shortened if it would swamp the message.
+fn preview(line: &str) -> String {
+    const MAX: usize = 40;
+    if line.chars().count() <= MAX {
+        format!("{line:?}")
+    } else {
+        let start: String = line.chars().take(MAX).collect();
+        format!("{start:?}...")
+    }
+}
+
+/// A run of non-blank lines.
+#[derive(Clone, Debug)]
+pub struct Section {
+    line: Pattern,
+    last: Option<Pattern>,
+    uniform_width: bool,
+}
+
+impl Section {
+    /// A section where every line matches `pattern` in full.
+    ///
+    /// # Panics
+    /// If `pattern` is not a valid regex.
+    #[must_use]
+    pub fn lines(pattern: &str) -> Self {
+        Self {
+            line: Pattern::new(pattern),
+            last: None,
+            uniform_width: false,
+        }
+    }
+
+    /// Match the final line against `pattern` instead, e.g. an operator row
+    /// under rows of numbers.
+    ///
+    /// # Panics
+    /// If `pattern` is not a valid regex.
+    #[must_use]
+    pub fn ending_with(mut self, pattern: &str) -> Self {
+        self.last = Some(Pattern::new(pattern));
+        self
+    }
+
+    /// Require every line to be as long as the first, as for grids.
+    #[must_use]
+    pub fn uniform_width(mut self) -> Self {
+        self.uniform_width = true;
+        self
+    }
+
+    /// Check numbered lines, reporting `start` if there are none.
+    fn validate(&self, start: usize, lines: &[(usize, &str)]) -> Result<(), InputError> {
+        let Some(&(_, first)) = lines.first() else {
+            return Err(InputError::malformed(start, "expected at least one line"));
+        };
+        for (i, &(number, line)) in lines.iter().enumerate() {
+            let pattern = match &self.last {
+                Some(last) if i + 1 == lines.len() => last,
+                _ => &self.line,
+            };
+            pattern
+                .check(line)
+                .map_err(|message| InputError::malformed(number, message))?;
+            if self.uniform_width && line.len() != first.len() {
+                return Err(InputError::malformed(
+                    number,
+                    format!("expected width {}, found {}", first.len(), line.len()),
+                ));
+            }
+        }
+        Ok(())
+    }
+}
+
+/// The expected shape of a puzzle input: one or more sections separated by
+/// single blank lines, ending in exactly one newline.
+#[derive(Clone, Debug)]
+pub struct Grammar(Vec<Section>);
+
+impl Grammar {
+    /// A single section where every line matches `pattern` in full.
+    ///
+    /// # Panics
+    /// If `pattern` is not a valid regex.
+    #[must_use]
+    pub fn lines(pattern: &str) -> Self {
+        Section::lines(pattern).into()
+    }
+
+    #[must_use]
+    pub fn sections(sections: impl IntoIterator<Item = Section>) -> Self {
+        Self(sections.into_iter().collect())
+    }
+
+    /// Check `input` against the grammar.
+    ///
+    /// # Errors
+    /// The first offending line: one that does not match its section, a
+    /// missing or extra blank line, or a missing final newline.
+    pub fn validate(&self, input: &str) -> Result<(), InputError> {
+        let Some(body) = input.strip_suffix('\n') else {
+            let message = if input.is_empty() {
+                "input is empty"
+            } else {
+                "missing final newline (truncated download?)"
+            };
+            return Err(InputError::malformed(input.lines().count().max(1), message));
+        };
+        let mut sections = vec![(1, Vec::new())];
+        let mut last_line = 0;
+        for (i, line) in body.split('\n').enumerate() {
+            last_line = i + 1;
+            if !line.is_empty() {
+                sections
+                    .last_mut()
+                    .expect("starts non-empty")
+                    .1
+                    .push((i + 1, line));
+            } else if sections.len() < self.0.len() {
+                sections.push((i + 2, Vec::new()));
+            } else {
+                return Err(InputError::malformed(i + 1, "unexpected blank line"));
+            }
+        }
+        if sections.len() < self.0.len() {
+            return Err(InputError::malformed(
+                last_line + 1,
+                format!(
+                    "expected {} sections separated by blank lines, found {}",
+                    self.0.len(),
+                    sections.len()
+                ),
+            ));
+        }
+        self.0
+            .iter()
+            .zip(&sections)
+            .try_for_each(|(section, (start, lines))| section.validate(*start, lines))
+    }
+}
+
+impl From<Section> for Grammar {
+    fn from(section: Section) -> Self {
+        Self(vec![section])
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn error_line(grammar: &Grammar, input: &str) -> (usize, String) {
+        match grammar.validate(input) {
+            Err(InputError::Malformed { line, message }) => (line, message),
+            other => panic!("expected a malformed input, got {other:?}"),
+        }
+    }
+
+    #[test]
+    fn accepts_matching_lines() {
+        let grammar = Grammar::lines(r"[LR]\d+");
+        assert_eq!(grammar.validate("L68\nR48\n"), Ok(()));
+        assert_eq!(
+            error_line(&grammar, "L68\nX48\n"),
+            (2, r#""X48" does not match `[LR]\d+`"#.into())
+        );
+        // The pattern must match the whole line, not just a prefix.
+        assert_eq!(error_line(&grammar, "L68x\n").0, 1);
+    }
+
+    #[test]
+    fn diagnoses_common_damage() {
+        let grammar = Grammar::lines(r"\d+");
+        assert_eq!(
+            error_line(&grammar, "12\r\n34\r\n"),
+            (
+                1,
+                "line ends with a carriage return (CRLF line endings?)".into()
+            )
+        );
+        assert_eq!(
+            error_line(&grammar, "12\n34 \n"),
+            (2, "trailing whitespace".into())
+        );
+        assert_eq!(
+            error_line(&grammar, "12\n3"),
+            (2, "missing final newline (truncated download?)".into())
+        );
+        assert_eq!(error_line(&grammar, ""), (1, "input is empty".into()));
+        assert_eq!(
+            error_line(&grammar, "12\n\n34\n"),
+            (2, "unexpected blank line".into())
+        );
+        let long = format!("{}x\n", "1".repeat(50));
+        assert!(
+            error_line(&grammar, &long)
+                .1
+                .starts_with(&format!("\"{}\"...", "1".repeat(40)))
+        );
+    }
+
+    #[test]
+    fn sections_and_widths() {
+        let grammar = Grammar::sections([Section::lines(r"\d+-\d+"), Section::lines(r"\d+")]);
+        assert_eq!(grammar.validate("3-5\n10-14\n\n1\n5\n"), Ok(()));
+        assert_eq!(
+            error_line(&grammar, "3-5\n10-14\n"),
+            (
+                3,
+                "expected 2 sections separated by blank lines, found 1".into()
+            )
+        );
+        assert_eq!(
+            error_line(&grammar, "3-5\n\n\n1\n"),
+            (3, "unexpected blank line".into())
+        );
+        assert_eq!(
+            error_line(&grammar, "3-5\n\n"),
+            (3, "expected at least one line".into())
+        );
+
+        let grid = Grammar::from(Section::lines("[.@]+").uniform_width());
+        assert_eq!(grid.validate("..@\n@@.\n"), Ok(()));
+        assert_eq!(
+            error_line(&grid, "..@\n@@\n"),
+            (2, "expected width 3, found 2".into())
+        );
+
+        let worksheet = Grammar::from(Section::lines(r"[\d ]+").ending_with(r"[-+*/ ]+"));
+        assert_eq!(worksheet.validate("1 2\n3 4\n+ *\n"), Ok(()));
+        assert_eq!(error_line(&worksheet, "1 2\n+ *\n3 4\n").0, 2);
+    }
+}
@@ -0,0 +1,165 @@
+use std::ops::{Index, IndexMut};
+
+/// A `(row, column)` position in a [`Grid`].
+pub type Pos = (usize, usize);
+
+/// Offsets to the four orthogonal neighbors, in reading order.
+pub const ORTHOGONAL: [(isize, isize); 4] = [(-1, 0), (0, -1), (0, 1), (1, 0)];
+
+/// Offsets to all eight neighbors, in reading order.
+pub const NEIGHBORS: [(isize, isize); 8] = [
+    (-1, -1),
+    (-1, 0),
+    (-1, 1),
+    (0, -1),
+    (0, 1),
+    (1, -1),
+    (1, 0),
+    (1, 1),
+];
+
+/// A rectangular grid of bytes, as found in most map-shaped puzzle inputs.
+#[derive(Clone, Debug, Default, PartialEq, Eq)]
+pub struct Grid {
+    rows: usize,
+    columns: usize,
+    cells: Vec<u8>,
+}
+
+impl Grid {
+    /// Build a grid of `rows` x `columns` filled with `fill`.
+    #[must_use]
+    pub fn new(rows: usize, columns: usize, fill: u8) -> Self {
+        Self {
+            rows,
+            columns,
+            cells: vec![fill; rows * columns],
+        }
+    }
+
+    #[must_use]
+    pub fn rows(&self) -> usize {
+        self.rows
+    }
+
+    #[must_use]
+    pub fn columns(&self) -> usize {
+        self.columns
+    }
+
+    /// The cell at `pos`, or `None` if it lies outside the grid.
+    #[must_use]
+    pub fn get(&self, (r, c): Pos) -> Option<u8> {
+        (r < self.rows && c < self.columns).then(|| self.cells[r * self.columns + c])
+    }
+
+    /// Step from `pos` by `(dr, dc)`, returning `None` if that leaves the grid.
+    #[must_use]
+    pub fn offset(&self, (r, c): Pos, (dr, dc): (isize, isize)) -> Option<Pos> {
+        let r = r.checked_add_signed(dr)?;
+        let c = c.checked_add_signed(dc)?;
+        (r < self.rows && c < self.columns).then_some((r, c))
+    }
+
+    /// In-bounds orthogonal neighbors of `pos`.
+    pub fn neighbors4(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        ORTHOGONAL.iter().filter_map(move |&d| self.offset(pos, d))
+    }
+
+    /// In-bounds neighbors of `pos`, including diagonals.
+    pub fn neighbors8(&self, pos: Pos) -> impl Iterator<Item = Pos> + '_ {
+        NEIGHBORS.iter().filter_map(move |&d| self.offset(pos, d))
+    }
+
+    /// Every position in the grid, in reading order.
+    pub fn positions(&self) -> impl Iterator<Item = Pos> + use<> {
+        let columns = self.columns;
+        (0..self.rows).flat_map(move |r| (0..columns).map(move |c| (r, c)))
+    }
+
+    /// The first position (in reading order) holding `value`.
+    #[must_use]
+    pub fn find(&self, value: u8) -> Option<Pos> {
+        self.cells
+            .iter()
+            .position(|&b| b == value)
+            .map(|i| (i / self.columns, i % self.columns))
+    }
+}
+
+impl Index<Pos> for Grid {
+    type Output = u8;
+
+    fn index(&self, (r, c): Pos) -> &Self::Output {
+        assert!(
+            r < self.rows && c < self.columns,
+            "({r}, {c}) out of bounds"
+        );
+        &self.cells[r * self.columns + c]
+    }
+}
+
+impl IndexMut<Pos> for Grid {
+    fn index_mut(&mut self, (r, c): Pos) -> &mut Self::Output {
+        assert!(
+            r < self.rows && c < self.columns,
+            "({r}, {c}) out of bounds"
+        );
+        &mut self.cells[r * self.columns + c]
+    }
+}
+
+impl From<&str> for Grid {
+    /// The grid is as wide as the longest line; shorter lines are padded
+    /// with `b' '`.
+    fn from(s: &str) -> Self {
+        let lines: Vec<&[u8]> = s.lines().map(str::as_bytes).collect();
+        let columns = lines.iter().map(|l| l.len()).max().unwrap_or(0);
+        let mut cells = Vec::with_capacity(lines.len() * columns);
+        for line in &lines {
+            cells.extend_from_slice(line);
+            cells.resize(cells.len() + columns - line.len(), b' ');
+        }
+        Self {
+            rows: lines.len(),
+            columns,
+            cells,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn from_str() {
+        let grid = Grid::from("123\n456\n780\n456\n");
+        assert_eq!(grid.rows(), 4);
+        assert_eq!(grid.columns(), 3);
+        assert_eq!(grid[(0, 0)], b'1');
+        assert_eq!(grid[(2, 2)], b'0');
+        assert_eq!(grid.get((4, 0)), None);
+        assert_eq!(grid.find(b'8'), Some((2, 1)));
+    }
+
+    #[test]
+    fn ragged_lines_are_padded() {
+        let grid = Grid::from("12\n3456\n7\n");
+        assert_eq!((grid.rows(), grid.columns()), (3, 4));
+        assert_eq!(grid[(1, 3)], b'6');
+        assert_eq!(grid[(0, 2)], b' ');
+        assert_eq!(grid[(2, 3)], b' ');
+    }
+
+    #[test]
+    fn neighbors_stay_in_bounds() {
+        let grid = Grid::new(3, 3, b'.');
+        assert_eq!(
+            grid.neighbors4((0, 0)).collect::<Vec<_>>(),
+            [(0, 1), (1, 0)]
+        );
+        assert_eq!(grid.neighbors8((1, 1)).count(), 8);
+        assert_eq!(grid.neighbors8((2, 2)).count(), 3);
+    }
+}
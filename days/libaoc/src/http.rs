@@ -0,0 +1,342 @@
+//! The one HTTP client used for adventofcode.com, following the site's
+//! automation guidelines: identify yourself in the User-Agent, throttle
+//! requests, and back off when the server struggles.
+//!
+//! The User-Agent is `$AOC_USER_AGENT`, else `libaoc/<version>` followed by
+//! `$AOC_CONTACT` (an email or repository URL) when set.
+//!
+//! The throttle is shared through a lock file in the per-user cache
+//! directory, `$XDG_CACHE_HOME/libaoc` or `~/.cache/libaoc`, so every
+//! checkout and working directory waits on the same clock.
+use std::ffi::OsString;
+use std::fs::{File, OpenOptions};
+use std::io::{self, Read, Seek, Write};
+use std::path::PathBuf;
+use std::sync::LazyLock;
+use std::thread;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+
+use crate::session::Session;
+use crate::{AocError, CACHE_LOCATION};
+
+const MIN_INTERVAL: Duration = Duration::from_secs(5);
+const TIMEOUT: Duration = Duration::from_secs(30);
+const RETRIES: u32 = 3;
+const BACKOFF: Duration = Duration::from_secs(1);
+
+/// An HTTP client; configure it by chaining, e.g.
+/// `Client::new(ua).min_interval(d).retries(2, d)`.
+#[derive(Debug, Clone)]
+pub struct Client {
+    user_agent: String,
+    min_interval: Duration,
+    timeout: Duration,
+    retries: u32,
+    backoff: Duration,
+    lock_path: PathBuf,
+}
+
+impl Client {
+    /// A client sending `user_agent`, with the default throttle, retries and
+    /// timeout, sharing its throttle with every process run by this user.
+    #[must_use]
+    pub fn new(user_agent: impl Into<String>) -> Self {
+        Self {
+            user_agent: user_agent.into(),
+            min_interval: MIN_INTERVAL,
+            timeout: TIMEOUT,
+            retries: RETRIES,
+            backoff: BACKOFF,
+            lock_path: lock_location(std::env::var_os("XDG_CACHE_HOME"), std::env::var_os("HOME")),
+        }
+    }
+
+    /// A client with the User-Agent from the environment (see the module docs).
+    #[must_use]
+    pub fn from_env() -> Self {
+        dotenv::dotenv().ok();
+        Self::new(user_agent(
+            std::env::var("AOC_USER_AGENT").ok(),
+            std::env::var("AOC_CONTACT").ok(),
+        ))
+    }
+
+    /// Wait at least `interval` after the previous request by any client
+    /// sharing the lock file.
+    #[must_use]
+    pub fn min_interval(mut self, interval: Duration) -> Self {
+        self.min_interval = interval;
+        self
+    }
+
+    /// Give up on a request after `timeout`.
+    #[must_use]
+    pub fn timeout(mut self, timeout: Duration) -> Self {
+        self.timeout = timeout;
+        self
+    }
+
+    /// Retry 5xx and 429 responses and failed connections `retries` times, waiting
+    /// `backoff`, then twice that, and so on.
+    #[must_use]
+    pub fn retries(mut self, retries: u32, backoff: Duration) -> Self {
+        self.retries = retries;
+        self.backoff = backoff;
+        self
+    }
+
+    /// Where the time of the last request is kept.
+    #[must_use]
+    pub fn lock_file(mut self, path: impl Into<PathBuf>) -> Self {
+        self.lock_path = path.into();
+        self
+    }
+
+    #[must_use]
+    pub fn user_agent(&self) -> &str {
+        &self.user_agent
+    }
+
+    /// GET `uri` with `session`'s cookie. Error statuses are returned rather
+    /// than raised, so their pages can be read; 5xx and 429 only once retries
+    /// run out.
+    ///
+    /// # Errors
+    /// `AocError::Network` if no response arrives, with the token redacted.
+    pub fn get(&self, uri: &str, session: &Session) -> Result<(u16, String), AocError> {
+        let agent: ureq::Agent = ureq::Agent::config_builder()
+            .http_status_as_error(false)
+            .timeout_global(Some(self.timeout))
+            .user_agent(self.user_agent.as_str())
+            .build()
+            .into();
+        let mut attempt = 0;
+        loop {
+            self.throttle()?;
+            let response = agent
+                .get(uri)
+                .header("Cookie", session.cookie())
+                .call()
+                .and_then(|mut response| {
+                    let body = response.body_mut().read_to_string()?;
+                    Ok((response.status().as_u16(), body))
+                });
+            if !is_transient(&response) || attempt == self.retries {
+                return response.map_err(|e| AocError::network(e, session));
+            }
+            thread::sleep(self.backoff * 2u32.pow(attempt));
+            attempt += 1;
+        }
+    }
+
+    /// Sleep until `min_interval` has passed since the last request, then
+    /// record this one. The lock file is held throughout, so concurrent
+    /// processes queue up.
+    fn throttle(&self) -> Result<(), AocError> {
+        self.try_throttle().map_err(|source| AocError::Throttle {
+            path: self.lock_path.clone(),
+            source,
+        })
+    }
+
+    fn try_throttle(&self) -> io::Result<()> {
+        if let Some(dir) = self.lock_path.parent() {
+            std::fs::create_dir_all(dir)?;
+        }
+        let mut file = OpenOptions::new()
+            .read(true)
+            .write(true)
+            .create(true)
+            .truncate(false)
+            .open(&self.lock_path)?;
+        file.lock()?;
+        let last = read_millis(&mut file);
+        let next = last.saturating_add(self.min_interval.as_millis());
+        let now = now_millis();
+        if next > now {
+            thread::sleep(Duration::from_millis(
+                u64::try_from(next - now).unwrap_or(u64::MAX),
+            ));
+        }
+        file.set_len(0)?;
+        file.rewind()?;
+        write!(file, "{}", now_millis())
+    }
+}
+
+/// Whether a request may succeed if repeated: the server was overloaded or
+/// rate limiting, or no connection could be made. Timeouts are not retried,
+/// since the request may have reached the server.
+fn is_transient(response: &Result<(u16, String), ureq::Error>) -> bool {
+    match response {
+        Ok((code, _)) => *code == 429 || *code >= 500,
+        Err(ureq::Error::ConnectionFailed) => true,
+        Err(ureq::Error::Io(e)) => matches!(
+            e.kind(),
+            io::ErrorKind::ConnectionRefused
+                | io::ErrorKind::ConnectionReset
+                | io::ErrorKind::ConnectionAborted
+                | io::ErrorKind::HostUnreachable
+                | io::ErrorKind::NetworkUnreachable
+        ),
+        Err(_) => false,
+    }
+}
+
+/// The lock file under `$XDG_CACHE_HOME`, else `$HOME/.cache`, else the input
+/// cache when neither is set.
+fn lock_location(cache_home: Option<OsString>, home: Option<OsString>) -> PathBuf {
+    let non_empty = |dir: Option<OsString>| dir.filter(|dir| !dir.is_empty()).map(PathBuf::from);
+    non_empty(cache_home)
+        .or_else(|| non_empty(home).map(|home| home.join(".cache")))
+        .map_or_else(
+            || PathBuf::from(format!("{CACHE_LOCATION}/.last-request")),
+            |dir| dir.join("libaoc/last-request"),
+        )
+}
+
+/// The client used for every request to adventofcode.com.
+pub fn client() -> &'static Client {
+    static CLIENT: LazyLock<Client> = LazyLock::new(Client::from_env);
+    &CLIENT
+}
+
+fn user_agent(custom: Option<String>, contact: Option<String>) -> String {
+    let base = concat!("libaoc/", env!("CARGO_PKG_VERSION"));
+    match (custom, contact) {
+        (Some(custom), _) => custom,
+        (None, Some(contact)) => format!("{base} ({contact})"),
+        (None, None) => base.to_owned(),
+    }
+}
+
+fn read_millis(file: &mut File) -> u128 {
+    let mut text = String::new();
+    file.read_to_string(&mut text).ok();
+    text.trim().parse().unwrap_or(0)
+}
+
+fn now_millis() -> u128 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .map_or(0, |elapsed| elapsed.as_millis())
+}
+
+#[cfg(test)]
+mod tests {
+    use std::net::TcpListener;
+    use std::time::Instant;
+
+    use super::*;
+    use crate::mock;
+
+    fn test_client(name: &str) -> Client {
+        let lock = std::env::temp_dir().join(format!("libaoc-{name}-{}", std::process::id()));
+        Client::new("libaoc-tests (test@example.com)")
+            .lock_file(lock)
+            .min_interval(Duration::ZERO)
+            .retries(0, Duration::ZERO)
+    }
+
+    fn session() -> Session {
+        Session::new("abc", "test").unwrap()
+    }
+
+    #[test]
+    fn user_agents() {
+        assert_eq!(user_agent(Some("mine".into()), Some("a@b".into())), "mine");
+        assert!(user_agent(None, Some("a@b".into())).ends_with(" (a@b)"));
+        assert!(user_agent(None, None).starts_with("libaoc/"));
+
+        let (base, server) = mock::serve(vec![(200, "ok")]);
+        test_client("agent").get(&base, &session()).unwrap();
+        let request = server.join().unwrap()[0].to_lowercase();
+        assert!(request.contains("user-agent: libaoc-tests (test@example.com)"));
+    }
+
+    #[test]
+    fn throttles_across_clients() {
+        let interval = Duration::from_millis(300);
+        let (base, server) = mock::serve(vec![(200, "1"), (200, "2")]);
+        let first = test_client("throttle").min_interval(interval);
+        let second = test_client("throttle").min_interval(interval);
+        let start = Instant::now();
+        first.get(&base, &session()).unwrap();
+        second.get(&base, &session()).unwrap();
+        assert!(start.elapsed() >= interval);
+        server.join().unwrap();
+    }
+
+    #[test]
+    fn retries_server_errors() {
+        let (base, server) = mock::serve(vec![(503, ""), (502, ""), (200, "ok"), (500, "")]);
+        let client = test_client("retry").retries(2, Duration::from_millis(1));
+        assert_eq!(client.get(&base, &session()).unwrap(), (200, "ok".into()));
+        let client = client.retries(0, Duration::ZERO);
+        assert_eq!(client.get(&base, &session()).unwrap().0, 500);
+        assert_eq!(server.join().unwrap().len(), 4);
+
+        let (base, server) = mock::serve(vec![(429, "slow down"), (200, "ok")]);
+        let client = test_client("rate-limit").retries(1, Duration::from_millis(1));
+        assert_eq!(client.get(&base, &session()).unwrap(), (200, "ok".into()));
+        assert_eq!(server.join().unwrap().len(), 2);
+
+        let (base, server) = mock::serve(vec![(404, "no")]);
+        let client = test_client("no-retry").retries(2, Duration::from_millis(1));
+        assert_eq!(client.get(&base, &session()).unwrap().0, 404);
+        assert_eq!(server.join().unwrap().len(), 1);
+    }
+
+    #[test]
+    fn retries_refused_connections() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        drop(listener);
+        let backoff = Duration::from_millis(200);
+        let client = test_client("refused").retries(1, backoff);
+        let start = Instant::now();
+        assert!(matches!(
+            client.get(&base, &session()),
+            Err(AocError::Network(_))
+        ));
+        assert!(start.elapsed() >= backoff);
+    }
+
+    #[test]
+    fn lock_locations() {
+        let dir = |s: &str| Some(OsString::from(s));
+        assert_eq!(
+            lock_location(dir("/xdg"), dir("/home/alex")),
+            PathBuf::from("/xdg/libaoc/last-request")
+        );
+        assert_eq!(
+            lock_location(dir(""), dir("/home/alex")),
+            PathBuf::from("/home/alex/.cache/libaoc/last-request")
+        );
+        assert_eq!(
+            lock_location(None, None),
+            PathBuf::from(format!("{CACHE_LOCATION}/.last-request"))
+        );
+    }
+
+    #[test]
+    fn times_out() {
+        let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+        let base = format!("http://{}", listener.local_addr().unwrap());
+        let server = thread::spawn(move || {
+            let (_stream, _) = listener.accept().unwrap();
+            thread::sleep(Duration::from_millis(500));
+        });
+        let client = test_client("timeout")
+            .timeout(Duration::from_millis(100))
+            .retries(2, Duration::from_millis(400));
+        let start = Instant::now();
+        assert!(matches!(
+            client.get(&base, &session()),
+            Err(AocError::Network(_))
+        ));
+        // Neither waited out nor retried.
+        assert!(start.elapsed() < Duration::from_millis(400));
+        server.join().unwrap();
+    }
+}
@@ -0,0 +1,257 @@
+//! Private leaderboards, from `/{year}/leaderboard/private/view/{id}.json`.
+//!
+//! The site asks that this endpoint is fetched at most once every 15 minutes,
+//! so the JSON is cached under `.input/leaderboard/` and reused until then.
+use std::collections::BTreeMap;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::time::{Duration, SystemTime};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::http::{self, Client};
+use crate::session::Session;
+use crate::{AocError, BASE_URI, CACHE_LOCATION};
+
+/// How long a fetched leaderboard is reused before asking the site again.
+pub const REFRESH: Duration = Duration::from_secs(15 * 60);
+
+#[derive(Debug, Error)]
+pub enum LeaderboardError {
+    #[error(transparent)]
+    Aoc(#[from] AocError),
+    #[error("leaderboard {id} returned {code}; check the id and that the profile can view it")]
+    Status { id: u64, code: u16 },
+    #[error("invalid leaderboard JSON in {path}: {source}")]
+    Parse {
+        path: PathBuf,
+        source: serde_json::Error,
+    },
+    #[error("failed to cache leaderboard at {path}: {source}")]
+    Cache { path: PathBuf, source: io::Error },
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Leaderboard {
+    pub event: String,
+    pub owner_id: u64,
+    pub members: BTreeMap<String, Member>,
+}
+
+#[derive(Clone, Debug, Deserialize)]
+pub struct Member {
+    pub id: u64,
+    /// `None` for anonymous users.
+    pub name: Option<String>,
+    pub stars: u32,
+    pub local_score: u64,
+    /// Unix time of the latest star; 0 if there are none.
+    pub last_star_ts: u64,
+    /// Stars by day.
+    #[serde(default)]
+    pub completion_day_level: BTreeMap<u8, DayStars>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct DayStars {
+    #[serde(rename = "1")]
+    pub part_1: Star,
+    #[serde(rename = "2")]
+    pub part_2: Option<Star>,
+}
+
+#[derive(Clone, Copy, Debug, Deserialize)]
+pub struct Star {
+    /// Unix time the star was earned.
+    pub get_star_ts: u64,
+    pub star_index: u64,
+}
+
+impl Leaderboard {
+    /// Members by local score, ties going to whoever got there first.
+    #[must_use]
+    pub fn standings(&self) -> Vec<&Member> {
+        let mut members: Vec<_> = self.members.values().collect();
+        members.sort_by_key(|m| (std::cmp::Reverse(m.local_score), m.last_star_ts, m.id));
+        members
+    }
+}
+
+impl Member {
+    /// The name shown on the site.
+    #[must_use]
+    pub fn display_name(&self) -> String {
+        self.name
+            .clone()
+            .unwrap_or_else(|| format!("(anonymous user #{})", self.id))
+    }
+
+    /// Seconds from the puzzle unlocking to `part` (1 or 2) of `day`.
+    #[must_use]
+    pub fn star_time(&self, year: u16, day: u8, part: u8) -> Option<u64> {
+        let stars = self.completion_day_level.get(&day)?;
+        let star = match part {
+            1 => stars.part_1,
+            2 => stars.part_2?,
+            _ => return None,
+        };
+        star.get_star_ts.checked_sub(unlock_time(year, day))
+    }
+
+    /// Seconds between the first and second star of `day`.
+    #[must_use]
+    pub fn delta(&self, day: u8) -> Option<u64> {
+        let stars = self.completion_day_level.get(&day)?;
+        stars
+            .part_2?
+            .get_star_ts
+            .checked_sub(stars.part_1.get_star_ts)
+    }
+}
+
+/// Unix time a puzzle unlocks: midnight US Eastern (UTC-5) on December `day`.
+#[must_use]
+pub fn unlock_time(year: u16, day: u8) -> u64 {
+    days_from_epoch(i64::from(year), 12, i64::from(day)) * 86_400 + 5 * 3600
+}
+
+/// Days from 1970-01-01 to a (post-1970) civil date.
+fn days_from_epoch(year: i64, month: i64, day: i64) -> u64 {
+    let year = if month <= 2 { year - 1 } else { year };
+    let era = year.div_euclid(400);
+    let year_of_era = year - era * 400;
+    let day_of_year = (153 * ((month + 9) % 12) + 2) / 5 + day - 1;
+    let day_of_era = year_of_era * 365 + year_of_era / 4 - year_of_era / 100 + day_of_year;
+    u64::try_from(era * 146_097 + day_of_era - 719_468).expect("date after 1970")
+}
+
+/// `seconds` as `H:MM:SS`; hours keep counting past a day.
+#[must_use]
+pub fn format_duration(seconds: u64) -> String {
+    format!(
+        "{}:{:02}:{:02}",
+        seconds / 3600,
+        seconds / 60 % 60,
+        seconds % 60
+    )
+}
+
+/// Leaderboard `id` for `year`, from the cache if fetched within [`REFRESH`].
+///
+/// # Errors
+/// If it cannot be fetched, parsed, or cached.
+pub fn load(year: u16, id: u64, session: &Session) -> Result<Leaderboard, LeaderboardError> {
+    let dir = PathBuf::from(format!("{CACHE_LOCATION}/leaderboard"));
+    load_from(http::client(), BASE_URI, &dir, year, id, session)
+}
+
+fn load_from(
+    client: &Client,
+    base: &str,
+    dir: &Path,
+    year: u16,
+    id: u64,
+    session: &Session,
+) -> Result<Leaderboard, LeaderboardError> {
+    let path = dir.join(format!("{year}/{id}.json"));
+    let text = match cached(&path) {
+        Some(text) => text,
+        None => {
+            let uri = format!("{base}/{year}/leaderboard/private/view/{id}.json");
+            let (code, text) = client.get(&uri, session)?;
+            if code != 200 {
+                return Err(LeaderboardError::Status { id, code });
+            }
+            // Logged-out requests get redirected to an HTML page.
+            if !text.trim_start().starts_with('{') {
+                return Err(AocError::SessionExpired.into());
+            }
+            let cache_error = |source| LeaderboardError::Cache {
+                path: path.clone(),
+                source,
+            };
+            std::fs::create_dir_all(path.parent().expect("cache path has a parent"))
+                .map_err(cache_error)?;
+            std::fs::write(&path, &text).map_err(cache_error)?;
+            text
+        }
+    };
+    serde_json::from_str(&text).map_err(|source| LeaderboardError::Parse { path, source })
+}
+
+/// The cached JSON at `path`, if it was written less than [`REFRESH`] ago.
+fn cached(path: &Path) -> Option<String> {
+    let modified = std::fs::metadata(path).ok()?.modified().ok()?;
+    let age = SystemTime::now().duration_since(modified).ok()?;
+    (age < REFRESH)
+        .then(|| std::fs::read_to_string(path).ok())
+        .flatten()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::mock;
+
+    const JSON: &str = r#"{
+        "event": "2025",
+        "owner_id": 1,
+        "members": {
+            "1": {"id": 1, "name": "alex", "stars": 3, "local_score": 10, "global_score": 0,
+                  "last_star_ts": 1764572400,
+                  "completion_day_level": {
+                      "1": {"1": {"get_star_ts": 1764565500, "star_index": 5},
+                            "2": {"get_star_ts": 1764566100, "star_index": 9}},
+                      "2": {"1": {"get_star_ts": 1764572400, "star_index": 20}}}},
+            "2": {"id": 2, "name": null, "stars": 0, "local_score": 0, "global_score": 0,
+                  "last_star_ts": 0, "completion_day_level": {}}
+        }
+    }"#;
+
+    #[test]
+    fn models_members() {
+        let board: Leaderboard = serde_json::from_str(JSON).unwrap();
+        let names: Vec<_> = board.standings().iter().map(|m| m.display_name()).collect();
+        assert_eq!(names, ["alex", "(anonymous user #2)"]);
+        let alex = &board.members["1"];
+        assert_eq!(alex.star_time(2025, 1, 1), Some(300));
+        assert_eq!(alex.star_time(2025, 1, 2), Some(900));
+        assert_eq!(alex.delta(1), Some(600));
+        assert_eq!((alex.star_time(2025, 2, 2), alex.delta(2)), (None, None));
+    }
+
+    #[test]
+    fn times() {
+        // 2025-12-01T05:00:00Z
+        assert_eq!(unlock_time(2025, 1), 1_764_565_200);
+        assert_eq!(unlock_time(2024, 25), 1_735_102_800);
+        assert_eq!(format_duration(59), "0:00:59");
+        assert_eq!(format_duration(90_061), "25:01:01");
+    }
+
+    #[test]
+    fn fetches_then_caches() {
+        let dir = std::env::temp_dir().join(format!("libaoc-leaderboard-{}", std::process::id()));
+        let client = Client::new("libaoc-tests")
+            .lock_file(dir.join("lock"))
+            .min_interval(Duration::ZERO)
+            .retries(0, Duration::ZERO);
+        let session = Session::new("abc", "test").unwrap();
+        let (base, server) = mock::serve(vec![(200, JSON), (404, "")]);
+
+        let board = load_from(&client, &base, &dir, 2025, 7, &session).unwrap();
+        assert_eq!(board.members.len(), 2);
+        // The second load is served from the cache, not the 404.
+        load_from(&client, &base, &dir, 2025, 7, &session).unwrap();
+        assert!(dir.join("2025/7.json").exists());
+        assert!(matches!(
+            load_from(&client, &base, &dir, 2025, 8, &session),
+            Err(LeaderboardError::Status { id: 8, code: 404 })
+        ));
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /2025/leaderboard/private/view/7.json "));
+        assert_eq!(requests.len(), 2);
+        std::fs::remove_dir_all(dir).unwrap();
+    }
+}
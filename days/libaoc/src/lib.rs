@@ -1,7 +1,35 @@
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
 
 use thiserror::Error;
 
+pub use libaoc_macros::aoc;
+
+use crate::profile::{Profile, ProfileError};
+use crate::session::Session;
+
+pub mod answers;
+pub mod auth;
+pub mod grammar;
+pub mod grid;
+pub mod http;
+pub mod leaderboard;
+pub mod manifest;
+pub mod math;
+#[cfg(test)]
+mod mock;
+pub mod profile;
+pub mod registry;
+pub mod rng;
+pub mod search;
+pub mod session;
+pub mod sim;
+pub mod stats;
+#[cfg(feature = "testing")]
+pub mod testing;
+
+// Lets `#[aoc]`, which names `::libaoc`, be used in this crate's tests.
+extern crate self as libaoc;
+
 // Enforce compiler knowledge that we are on at least 32 bit machine
 const _: () = assert!(usize::BITS >= 32, "usize must be at least 32 bits");
 
@@ -13,21 +41,67 @@ pub enum AocError {
     #[error("failed to load input from cache: {0}")]
     Cache(#[from] std::io::Error),
 
+    /// The message has the session token redacted.
     #[error("failed to download input from adventofcode.com: {0}")]
-    Network(#[from] ureq::Error),
+    Network(String),
+
+    #[error("failed to use request lock file {path}: {source}")]
+    Throttle {
+        path: PathBuf,
+        source: std::io::Error,
+    },
+
+    #[error("session token is expired or invalid; log in to adventofcode.com and replace it")]
+    SessionExpired,
+
+    #[error(transparent)]
+    Profile(#[from] ProfileError),
+}
+
+impl AocError {
+    /// A network error for a request made with `session`, with the token
+    /// removed from its message.
+    pub fn network(error: impl std::fmt::Display, session: &Session) -> Self {
+        Self::Network(session.redact(&error.to_string()))
+    }
+}
+
+/// Why a puzzle input could not be solved.
+#[derive(Clone, Debug, Error, PartialEq, Eq)]
+pub enum InputError {
+    /// The input does not match the puzzle's format. Lines are 1-based.
+    #[error("line {line}: {message}")]
+    Malformed { line: usize, message: String },
+
+    /// The input parsed, but has no representable answer (e.g. it overflows).
+    #[error("unsolvable input: {0}")]
+    Unsolvable(String),
+}
+
+impl InputError {
+    pub fn malformed(line: usize, message: impl Into<String>) -> Self {
+        Self::Malformed {
+            line,
+            message: message.into(),
+        }
+    }
 }
 
-/// Get the input, either from a cached location or by downloading the input
+/// Get the raw input for `profile`, either from its cache or by downloading it
 /// # Errors
 /// `AoCError::Cache` => The cache location exists but there was an error loading it
 /// `AoCError::Network` => There was an issue downloading the input
-/// # Panics
-/// This function panics if the SESSION env var is not defined.
+/// `AoCError::SessionExpired` => adventofcode.com asked us to log in
 #[inline]
-fn get_input(year: u16, day: u8, session: &str) -> Result<String, AocError> {
-    match load_from_cache(year, day) {
+pub fn get_input(profile: &Profile, year: u16, day: u8) -> Result<String, AocError> {
+    get_input_in(Path::new(CACHE_LOCATION), profile, year, day)
+}
+
+/// [`get_input`], caching under `cache`.
+fn get_input_in(cache: &Path, profile: &Profile, year: u16, day: u8) -> Result<String, AocError> {
+    match load_from_cache(cache, profile.name(), year, day) {
         Ok(Some(input)) => Ok(input),
-        _ => Ok(download_input(year, day, session)?),
+        _ => download_input(cache, profile, year, day),
     }
 }
 
@@ -35,11 +109,16 @@ fn get_input(year: u16, day: u8, session: &str) -> Result<String, AocError> {
 /// # Errors
 /// `std::io::Error` => The file does not exist or could not be read
 #[inline]
-fn load_from_cache(year: u16, day: u8) -> Result<Option<String>, std::io::Error> {
-    let path = cached_path(year, day);
+fn load_from_cache(
+    cache: &Path,
+    profile: Option<&str>,
+    year: u16,
+    day: u8,
+) -> Result<Option<String>, std::io::Error> {
+    let path = cached_path(cache, profile, year, day);
     std::fs::create_dir_all(path.parent().expect("Unable to define the cache directory"))
         .expect("Unable to create cache directory.");
-    let input = std::fs::read_to_string(cached_path(year, day))?;
+    let input = std::fs::read_to_string(path)?;
     if input.is_empty() {
         Ok(None)
     } else {
@@ -47,33 +126,62 @@ fn load_from_cache(year: u16, day: u8) -> Result<Option<String>, std::io::Error>
     }
 }
 
+/// The cached input for `profile`, if it has been downloaded. Unlike
+/// [`get_input`] this never touches the network.
+#[must_use]
+pub fn cached_input(profile: Option<&str>, year: u16, day: u8) -> Option<String> {
+    std::fs::read_to_string(cached_path(Path::new(CACHE_LOCATION), profile, year, day))
+        .ok()
+        .filter(|input| !input.is_empty())
+}
+
+/// Named profiles get their own cache directory; the unnamed `SESSION`
+/// profile keeps the original layout.
 #[inline]
-fn cached_path(year: u16, day: u8) -> PathBuf {
-    PathBuf::from(format!("{CACHE_LOCATION}/{year}/{day}.txt"))
+fn cached_path(cache: &Path, profile: Option<&str>, year: u16, day: u8) -> PathBuf {
+    match profile {
+        Some(name) => cache.join(format!("{name}/{year}/{day}.txt")),
+        None => cache.join(format!("{year}/{day}.txt")),
+    }
 }
 
-/// This is a thin wrapper around the synchronous http request `ureq::get`
+/// Download the input and cache it.
 /// # Errors
-/// This function can return a ureq:Error type.
+/// See [`fetch_input`].
 #[inline]
-fn download_input(year: u16, day: u8, session: &str) -> Result<String, ureq::Error> {
-    let input = ureq::get(request_uri(year, day))
-        .header("Cookie", format!("session={session}"))
-        .call()?
-        .body_mut()
-        .read_to_string()?;
-    cache_input(&input, year, day);
+fn download_input(cache: &Path, profile: &Profile, year: u16, day: u8) -> Result<String, AocError> {
+    let input = fetch_input(
+        http::client(),
+        &request_uri(BASE_URI, year, day),
+        profile.session(),
+    )?;
+    cache_input(cache, &input, profile.name(), year, day);
     Ok(input)
 }
 
-fn cache_input(input: &str, year: u16, day: u8) {
-    let path = cached_path(year, day);
+/// # Errors
+/// `AocError::SessionExpired` if the site answers with its "please log in"
+/// page, else `AocError::Network` for any other failure.
+fn fetch_input(client: &http::Client, uri: &str, session: &Session) -> Result<String, AocError> {
+    let (code, body) = client.get(uri, session)?;
+    match code {
+        200..300 => Ok(body),
+        400..500 if body.to_lowercase().contains("log in") => Err(AocError::SessionExpired),
+        _ => Err(AocError::network(
+            format!("{uri} returned {code}: {}", body.trim()),
+            session,
+        )),
+    }
+}
+
+fn cache_input(cache: &Path, input: &str, profile: Option<&str>, year: u16, day: u8) {
+    let path = cached_path(cache, profile, year, day);
     std::fs::write(path, input).expect("Unable to cache input.");
 }
 
 #[inline]
-fn request_uri(year: u16, day: u8) -> String {
-    format!("{BASE_URI}/{year}/day/{day}/input")
+fn request_uri(base: &str, year: u16, day: u8) -> String {
+    format!("{base}/{year}/day/{day}/input")
 }
 
 #[derive(Debug, PartialEq, Eq)]
@@ -105,17 +213,79 @@ where
 /// >>>     _ => Part::Both,
 /// >>> }
 fn part_from_args() -> Part {
-    let arg = std::env::args().nth(1).unwrap_or_default();
-    arg.into()
+    parse_args(std::env::args().skip(1)).0
+}
+
+/// The part and profile selected by `[part] [--profile NAME]`, in any order.
+fn parse_args(args: impl IntoIterator<Item = String>) -> (Part, Option<String>) {
+    let mut args = args.into_iter();
+    let (mut part, mut profile) = (None, None);
+    while let Some(arg) = args.next() {
+        if arg == "--profile" {
+            profile = args.next();
+        } else if let Some(name) = arg.strip_prefix("--profile=") {
+            profile = Some(name.to_owned());
+        } else if part.is_none() {
+            part = Some(Part::from(arg));
+        }
+    }
+    (part.unwrap_or(Part::Both), profile)
 }
 
+/// Load the input with [`normalize`]d line endings.
+///
 /// # Panics
-/// If SESSION env var is not set.
+/// If no profile or SESSION env var is set, or the input cannot be loaded.
 #[must_use]
 pub fn load_input(year: u16, day: u8) -> String {
-    dotenv::dotenv().ok();
-    let session = std::env::var("SESSION").expect("SESSION env var not defined.");
-    get_input(year, day, &session).expect("Unable to load input")
+    load_input_in(Path::new(CACHE_LOCATION), &args_profile(), year, day)
+}
+
+/// [`load_input`] for `profile`, caching under `cache`.
+fn load_input_in(cache: &Path, profile: &Profile, year: u16, day: u8) -> String {
+    normalize(
+        &get_input_in(cache, profile, year, day)
+            .unwrap_or_else(|e| panic!("Unable to load input: {e}")),
+    )
+}
+
+/// Load the input's bytes exactly as they were downloaded or cached, for the
+/// profile given by `--profile` (see [`profile::resolve`]).
+///
+/// # Panics
+/// If no profile or SESSION env var is set, or the input cannot be loaded.
+#[must_use]
+pub fn load_input_raw(year: u16, day: u8) -> Vec<u8> {
+    load_input_raw_in(Path::new(CACHE_LOCATION), &args_profile(), year, day)
+}
+
+/// [`load_input_raw`] for `profile`, caching under `cache`. The cache is read
+/// as bytes, so it need not be valid UTF-8.
+fn load_input_raw_in(cache: &Path, profile: &Profile, year: u16, day: u8) -> Vec<u8> {
+    match std::fs::read(cached_path(cache, profile.name(), year, day)) {
+        Ok(input) if !input.is_empty() => input,
+        _ => get_input_in(cache, profile, year, day)
+            .unwrap_or_else(|e| panic!("Unable to load input: {e}"))
+            .into_bytes(),
+    }
+}
+
+/// The profile given by `--profile`, or the default.
+fn args_profile() -> Profile {
+    let (_, name) = parse_args(std::env::args().skip(1));
+    profile::resolve(name.as_deref()).unwrap_or_else(|e| panic!("{e}"))
+}
+
+/// Convert CRLF line endings to LF and end non-empty input with exactly one
+/// newline, the shape every solver expects.
+#[must_use]
+pub fn normalize(input: &str) -> String {
+    let mut input = input.replace("\r\n", "\n");
+    input.truncate(input.trim_end_matches('\n').len());
+    if !input.is_empty() {
+        input.push('\n');
+    }
+    input
 }
 
 /// # Panics
@@ -127,12 +297,36 @@ pub fn evaluate<F1: for<'a> Fn(&'a str) -> usize, F2: for<'a> Fn(&'a str) -> usi
     day: u8,
 ) {
     let input = load_input(year, day);
+    solve(&f1, &f2, &input);
+}
+
+/// Like [`evaluate`], but first checks the input against the day's `grammar`,
+/// exiting with the first offending line instead of running the solvers.
+///
+/// # Panics
+/// This function panics if the input cannot be loaded
+pub fn evaluate_checked<F1: for<'a> Fn(&'a str) -> usize, F2: for<'a> Fn(&'a str) -> usize>(
+    f1: F1,
+    f2: F2,
+    grammar: &grammar::Grammar,
+    year: u16,
+    day: u8,
+) {
+    let input = load_input(year, day);
+    if let Err(e) = grammar.validate(&input) {
+        eprintln!("Invalid input for {year} day {day}: {e}");
+        std::process::exit(1);
+    }
+    solve(&f1, &f2, &input);
+}
+
+fn solve(f1: &dyn Fn(&str) -> usize, f2: &dyn Fn(&str) -> usize, input: &str) {
     let part = part_from_args();
     if part == Part::One || part == Part::Both {
-        println!("Part One: {}", f1(&input));
+        println!("Part One: {}", f1(input));
     }
     if part == Part::Two || part == Part::Both {
-        println!("Part Two: {}", f2(&input));
+        println!("Part Two: {}", f2(input));
     }
 }
 
@@ -142,13 +336,95 @@ mod tests {
 
     #[test]
     fn uri_path_works() {
-        let uri = request_uri(2025, 1);
+        let uri = request_uri(BASE_URI, 2025, 1);
         assert_eq!(uri, "https://adventofcode.com/2025/day/1/input");
     }
     #[test]
     fn cache_path_works() {
-        let uri = cached_path(2025, 1);
+        let cache = Path::new(CACHE_LOCATION);
+        let uri = cached_path(cache, None, 2025, 1);
         assert_eq!(uri, PathBuf::from("./.input/2025/1.txt"));
+        let uri = cached_path(cache, Some("sam"), 2025, 1);
+        assert_eq!(uri, PathBuf::from("./.input/sam/2025/1.txt"));
+    }
+    #[test]
+    fn args_select_part_and_profile() {
+        let args = |s: &str| parse_args(s.split_whitespace().map(String::from));
+        assert_eq!(args(""), (Part::Both, None));
+        assert_eq!(args("1"), (Part::One, None));
+        assert_eq!(args("2 --profile sam"), (Part::Two, Some("sam".into())));
+        assert_eq!(args("--profile sam 1"), (Part::One, Some("sam".into())));
+        assert_eq!(args("--profile=kim"), (Part::Both, Some("kim".into())));
+    }
+    #[test]
+    fn network_errors_are_redacted() {
+        let session = Session::new("53616c746564", "test").unwrap();
+        let error = AocError::network("bad header: session=53616c746564 (53616c746564)", &session);
+        assert_eq!(
+            error.to_string(),
+            "failed to download input from adventofcode.com: \
+             bad header: session=[redacted] ([redacted])"
+        );
+    }
+    #[test]
+    fn detects_expired_sessions() {
+        let session = Session::new("abc", "test").unwrap();
+        let (base, server) = mock::serve(vec![
+            (200, "3-5\n"),
+            (
+                400,
+                "Puzzle inputs differ by user.  Please log in to get your puzzle input.\n",
+            ),
+            (
+                404,
+                "Please don't repeatedly request this endpoint before it unlocks!",
+            ),
+        ]);
+        let uri = request_uri(&base, 2025, 5);
+        let client = http::Client::new("libaoc-tests")
+            .lock_file(std::env::temp_dir().join(format!("libaoc-fetch-{}", std::process::id())))
+            .min_interval(std::time::Duration::ZERO);
+        assert_eq!(fetch_input(&client, &uri, &session).unwrap(), "3-5\n");
+        assert!(matches!(
+            fetch_input(&client, &uri, &session),
+            Err(AocError::SessionExpired)
+        ));
+        assert!(matches!(
+            fetch_input(&client, &uri, &session),
+            Err(AocError::Network(message)) if message.contains("404")
+        ));
+        let requests = server.join().unwrap();
+        assert!(requests[0].starts_with("GET /2025/day/5/input "));
+    }
+    #[test]
+    fn loaded_inputs_are_normalized() {
+        let cache = std::env::temp_dir().join(format!("libaoc-crlf-{}", std::process::id()));
+        std::fs::create_dir_all(cache.join("2025")).unwrap();
+        std::fs::write(cache.join("2025/1.txt"), "L68\r\nR48\r\n\r\n").unwrap();
+        let profile = Profile::new(None, Session::new("53616c746564", "test").unwrap());
+        let input = load_input_in(&cache, &profile, 2025, 1);
+        std::fs::remove_dir_all(&cache).unwrap();
+        assert_eq!(input, "L68\nR48\n");
+    }
+    #[test]
+    fn raw_inputs_are_untouched() {
+        let cache = std::env::temp_dir().join(format!("libaoc-raw-{}", std::process::id()));
+        std::fs::create_dir_all(cache.join("2025")).unwrap();
+        let bytes = b"L68\r\nR\xff48\r\n\r\n";
+        std::fs::write(cache.join("2025/1.txt"), bytes).unwrap();
+        let profile = Profile::new(None, Session::new("53616c746564", "test").unwrap());
+        let input = load_input_raw_in(&cache, &profile, 2025, 1);
+        std::fs::remove_dir_all(&cache).unwrap();
+        assert_eq!(input, bytes);
+    }
+    #[test]
+    fn normalizes_line_endings() {
+        assert_eq!(normalize("L68\r\nR48\r\n"), "L68\nR48\n");
+        assert_eq!(normalize("L68\nR48"), "L68\nR48\n");
+        assert_eq!(normalize("3-5\r\n\r\n1\r\n\r\n\r\n"), "3-5\n\n1\n");
+        assert_eq!(normalize(" 1 \n+  \n"), " 1 \n+  \n");
+        assert_eq!(normalize("\r\n\n"), "");
+        assert_eq!(normalize(""), "");
     }
     #[test]
     fn parts_from_str() {
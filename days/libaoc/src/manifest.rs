@@ -0,0 +1,418 @@
+//! Which puzzle a day crate solves, read from its own `Cargo.toml` so that
+//! nothing else has to repeat it:
+//!
+//! ```toml
+//! [package]
+//! name = "aoc2024-day01"  # or just "day01"
+//!
+//! [package.metadata.aoc]
+//! year = 2024             # needed when the name has no year
+//! day = 1                 # optional; must agree with the name
+//! ```
+//!
+//! The metadata is found by scanning for the `[package.metadata.aoc]` header
+//! line, as above; an inline table (`aoc = { year = 2024 }`) or dotted keys
+//! (`aoc.year = 2024`) under `[package.metadata]` are not read.
+//!
+//! [`puzzle!`](crate::puzzle!) reads this at compile time, so a day crate that
+//! names no puzzle, or whose name and metadata disagree, does not build.
+use std::fmt;
+
+use serde::Deserialize;
+use thiserror::Error;
+
+#[derive(Debug, Error)]
+pub enum ManifestError {
+    #[error("invalid Cargo.toml: {0}")]
+    Parse(#[from] toml::de::Error),
+    #[error("{crate_name}: no {field} under a [package.metadata.aoc] header or in the crate name")]
+    Missing {
+        crate_name: String,
+        field: &'static str,
+    },
+    #[error(
+        "{crate_name}: [package.metadata.aoc] {field} is {metadata}, but the crate name says {name}"
+    )]
+    Mismatch {
+        crate_name: String,
+        field: &'static str,
+        metadata: u16,
+        name: u16,
+    },
+    #[error("{crate_name}: {field} {value} is not a puzzle {field}")]
+    OutOfRange {
+        crate_name: String,
+        field: &'static str,
+        value: u16,
+    },
+}
+
+/// One day's puzzle.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct Puzzle {
+    pub year: u16,
+    pub day: u8,
+}
+
+impl fmt::Display for Puzzle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} day {}", self.year, self.day)
+    }
+}
+
+#[derive(Deserialize)]
+struct Manifest {
+    package: Package,
+}
+
+#[derive(Deserialize)]
+struct Package {
+    name: String,
+}
+
+/// The puzzle solved by the crate whose `Cargo.toml` is `manifest`.
+///
+/// # Errors
+/// If the manifest is invalid, the year or day cannot be found, or the
+/// metadata and crate name disagree.
+pub fn puzzle(manifest: &str) -> Result<Puzzle, ManifestError> {
+    let crate_name = toml::from_str::<Manifest>(manifest)?.package.name;
+    resolve(&crate_name, manifest).map_err(|problem| problem.error(crate_name))
+}
+
+/// [`puzzle`] for the crate called `name`, for use in a `const` by
+/// [`puzzle!`] so that a crate that names no puzzle fails to build.
+///
+/// # Panics
+/// If the year or day cannot be found, or the metadata and name disagree.
+#[doc(hidden)]
+#[must_use]
+pub const fn checked(name: &str, manifest: &str) -> Puzzle {
+    match resolve(name, manifest) {
+        Ok(puzzle) => puzzle,
+        Err(problem) => panic!("{}", problem.message()),
+    }
+}
+
+#[derive(Clone, Copy)]
+enum Field {
+    Year,
+    Day,
+}
+
+impl Field {
+    const fn name(self) -> &'static str {
+        match self {
+            Field::Year => "year",
+            Field::Day => "day",
+        }
+    }
+}
+
+/// A [`ManifestError`] without the crate name, so it can be made in a `const`.
+#[derive(Clone, Copy)]
+enum Problem {
+    Missing(Field),
+    Mismatch(Field, u16, u16),
+    OutOfRange(Field, u16),
+}
+
+impl Problem {
+    fn error(self, crate_name: String) -> ManifestError {
+        match self {
+            Problem::Missing(field) => ManifestError::Missing {
+                crate_name,
+                field: field.name(),
+            },
+            Problem::Mismatch(field, metadata, name) => ManifestError::Mismatch {
+                crate_name,
+                field: field.name(),
+                metadata,
+                name,
+            },
+            Problem::OutOfRange(field, value) => ManifestError::OutOfRange {
+                crate_name,
+                field: field.name(),
+                value,
+            },
+        }
+    }
+
+    /// What went wrong, for a compile-time panic, which cannot format numbers.
+    const fn message(self) -> &'static str {
+        match self {
+            Problem::Missing(Field::Year) => {
+                "no year under a [package.metadata.aoc] header or in the crate name"
+            }
+            Problem::Missing(Field::Day) => {
+                "no day under a [package.metadata.aoc] header or in the crate name"
+            }
+            Problem::Mismatch(Field::Year, ..) => {
+                "[package.metadata.aoc] year disagrees with the crate name"
+            }
+            Problem::Mismatch(Field::Day, ..) => {
+                "[package.metadata.aoc] day disagrees with the crate name"
+            }
+            Problem::OutOfRange(Field::Year, _) => "the year is not a puzzle year",
+            Problem::OutOfRange(Field::Day, _) => "the day is not a puzzle day",
+        }
+    }
+}
+
+/// The puzzle for a crate called `name`. This is `const` so that [`puzzle!`]
+/// can run it at compile time, hence the loops over bytes and lack of `?`.
+const fn resolve(name: &str, manifest: &str) -> Result<Puzzle, Problem> {
+    let year = match pick(
+        Field::Year,
+        metadata(manifest, "year"),
+        from_name(name, "aoc"),
+        2015,
+        9999,
+    ) {
+        Ok(year) => year,
+        Err(problem) => return Err(problem),
+    };
+    let day = match pick(
+        Field::Day,
+        metadata(manifest, "day"),
+        from_name(name, "day"),
+        1,
+        25,
+    ) {
+        Ok(day) => day,
+        Err(problem) => return Err(problem),
+    };
+    Ok(Puzzle {
+        year,
+        day: day as u8,
+    })
+}
+
+/// The value from the metadata or else the name, which must agree if both
+/// are given, and lie in `min..=max`.
+const fn pick(
+    field: Field,
+    metadata: Option<u16>,
+    from_name: Option<u16>,
+    min: u16,
+    max: u16,
+) -> Result<u16, Problem> {
+    let value = match (metadata, from_name) {
+        (Some(metadata), Some(from_name)) if metadata != from_name => {
+            return Err(Problem::Mismatch(field, metadata, from_name));
+        }
+        (Some(value), _) | (None, Some(value)) => value,
+        (None, None) => return Err(Problem::Missing(field)),
+    };
+    if value < min || value > max {
+        return Err(Problem::OutOfRange(field, value));
+    }
+    Ok(value)
+}
+
+/// The number after `prefix` in a segment of a crate name like
+/// `aoc2024-day01` or `day01`, split on `-` and `_`.
+const fn from_name(name: &str, prefix: &str) -> Option<u16> {
+    let name = name.as_bytes();
+    let mut start = 0;
+    while start < name.len() {
+        let mut end = start;
+        while end < name.len() && name[end] != b'-' && name[end] != b'_' {
+            end += 1;
+        }
+        if starts_with(name, start, end, prefix.as_bytes())
+            && let Some(number) = number(name, start + prefix.len(), end)
+        {
+            return Some(number);
+        }
+        start = end + 1;
+    }
+    None
+}
+
+/// `key = N` from the manifest's `[package.metadata.aoc]` table, which must
+/// be written as a table rather than inline.
+const fn metadata(manifest: &str, key: &str) -> Option<u16> {
+    let (text, key) = (manifest.as_bytes(), key.as_bytes());
+    let mut in_table = false;
+    let mut start = 0;
+    while start < text.len() {
+        let mut end = start;
+        while end < text.len() && text[end] != b'\n' {
+            end += 1;
+        }
+        let first = skip_spaces(text, start, end);
+        if first < end && text[first] == b'[' {
+            in_table = starts_with(text, first, end, b"[package.metadata.aoc]");
+        } else if in_table && starts_with(text, first, end, key) {
+            let equals = skip_spaces(text, first + key.len(), end);
+            if equals < end && text[equals] == b'=' {
+                let digits = skip_spaces(text, equals + 1, end);
+                let mut stop = digits;
+                while stop < end && text[stop].is_ascii_digit() {
+                    stop += 1;
+                }
+                return number(text, digits, stop);
+            }
+        }
+        start = end + 1;
+    }
+    None
+}
+
+/// Whether `text[start..end]` starts with `prefix`.
+const fn starts_with(text: &[u8], start: usize, end: usize, prefix: &[u8]) -> bool {
+    if end - start < prefix.len() {
+        return false;
+    }
+    let mut i = 0;
+    while i < prefix.len() {
+        if text[start + i] != prefix[i] {
+            return false;
+        }
+        i += 1;
+    }
+    true
+}
+
+/// The first index in `start..end` that is not a space or tab.
+const fn skip_spaces(text: &[u8], mut start: usize, end: usize) -> usize {
+    while start < end && (text[start] == b' ' || text[start] == b'\t') {
+        start += 1;
+    }
+    start
+}
+
+/// `text[start..end]` as a number, if it is only digits and fits a `u16`.
+const fn number(text: &[u8], start: usize, end: usize) -> Option<u16> {
+    if start >= end {
+        return None;
+    }
+    let mut value: u32 = 0;
+    let mut i = start;
+    while i < end {
+        if !text[i].is_ascii_digit() {
+            return None;
+        }
+        value = value * 10 + (text[i] - b'0') as u32;
+        if value > u16::MAX as u32 {
+            return None;
+        }
+        i += 1;
+    }
+    Some(value as u16)
+}
+
+/// The calling crate's `Cargo.toml`, embedded at compile time.
+#[macro_export]
+macro_rules! manifest {
+    () => {
+        include_str!(concat!(env!("CARGO_MANIFEST_DIR"), "/Cargo.toml"))
+    };
+}
+
+/// The calling crate's [`Puzzle`](crate::manifest::Puzzle), from its name and
+/// [`manifest!`]. This is worked out at compile time, so a crate whose
+/// manifest does not name a puzzle, or whose name and metadata disagree,
+/// fails to build.
+#[macro_export]
+macro_rules! puzzle {
+    () => {{
+        const PUZZLE: $crate::manifest::Puzzle =
+            $crate::manifest::checked(env!("CARGO_PKG_NAME"), $crate::manifest!());
+        PUZZLE
+    }};
+}
+
+/// A day's `main`: solve the input of the crate's [`puzzle!`](crate::puzzle!),
+/// first checking it against `grammar()` if given.
+///
+/// ```ignore
+/// libaoc::aoc_main!(part_1, part_2, grammar);
+/// ```
+#[macro_export]
+macro_rules! aoc_main {
+    ($part_1:expr, $part_2:expr $(,)?) => {
+        fn main() {
+            let puzzle = $crate::puzzle!();
+            $crate::evaluate($part_1, $part_2, puzzle.year, puzzle.day);
+        }
+    };
+    ($part_1:expr, $part_2:expr, $grammar:expr $(,)?) => {
+        fn main() {
+            let puzzle = $crate::puzzle!();
+            $crate::evaluate_checked($part_1, $part_2, &$grammar(), puzzle.year, puzzle.day);
+        }
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn manifest(name: &str, metadata: &str) -> String {
+        format!("[package]\nname = \"{name}\"\n\n[package.metadata.aoc]\n{metadata}\n")
+    }
+
+    #[test]
+    fn reads_metadata_and_names() {
+        let puzzle = |name, metadata| super::puzzle(&manifest(name, metadata));
+        let expected = Puzzle { year: 2024, day: 1 };
+        assert_eq!(puzzle("aoc2024-day01", "").unwrap(), expected);
+        assert_eq!(puzzle("day01", "year = 2024").unwrap(), expected);
+        assert_eq!(puzzle("aoc2024_day01", "day = 1").unwrap(), expected);
+        assert_eq!(puzzle("solver", "year = 2024\nday = 1").unwrap(), expected);
+        assert_eq!(expected.to_string(), "2024 day 1");
+    }
+
+    #[test]
+    fn rejects_missing_and_mismatched_puzzles() {
+        let error = |name, metadata| puzzle(&manifest(name, metadata)).unwrap_err().to_string();
+        assert_eq!(
+            error("day01", ""),
+            "day01: no year under a [package.metadata.aoc] header or in the crate name"
+        );
+        assert_eq!(
+            error("aoc2024-day01", "day = 2"),
+            "aoc2024-day01: [package.metadata.aoc] day is 2, but the crate name says 1"
+        );
+        assert_eq!(
+            error("aoc2024-day26", ""),
+            "aoc2024-day26: day 26 is not a puzzle day"
+        );
+        assert!(matches!(puzzle("[package"), Err(ManifestError::Parse(_))));
+    }
+
+    #[test]
+    fn reads_only_the_aoc_table() {
+        let manifest = "[package]\nname = \"solver\"\n\n\
+                        [package.metadata.aoc]\n  year=2024 # the year\n\
+                        [package.metadata.aoc.extra]\nday = 2\n\n\
+                        [dependencies]\nday = 3\n";
+        assert!(matches!(
+            puzzle(manifest),
+            Err(ManifestError::Missing { field: "day", .. })
+        ));
+        let manifest = manifest.replace("year=2024", "year=2024\nday = 1");
+        assert_eq!(puzzle(&manifest).unwrap(), Puzzle { year: 2024, day: 1 });
+        for unread in ["aoc = { year = 2024 }", "aoc.year = 2024"] {
+            let manifest = format!("[package]\nname = \"day01\"\n[package.metadata]\n{unread}\n");
+            assert!(matches!(
+                puzzle(&manifest),
+                Err(ManifestError::Missing { field: "year", .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn checks_at_compile_time() {
+        const PUZZLE: Puzzle = checked("aoc2024-day01", "");
+        assert_eq!(PUZZLE, Puzzle { year: 2024, day: 1 });
+    }
+
+    #[test]
+    #[should_panic(expected = "[package.metadata.aoc] day disagrees with the crate name")]
+    fn checked_rejects_mismatches() {
+        let _ = checked("aoc2024-day01", "[package.metadata.aoc]\nday = 2\n");
+    }
+}
@@ -0,0 +1,279 @@
+/// Greatest common divisor.
+#[must_use]
+pub fn gcd(mut a: usize, mut b: usize) -> usize {
+    while b != 0 {
+        (a, b) = (b, a % b);
+    }
+    a
+}
+
+/// Least common multiple. `lcm(0, n)` is 0.
+#[must_use]
+pub fn lcm(a: usize, b: usize) -> usize {
+    if a == 0 || b == 0 {
+        0
+    } else {
+        a / gcd(a, b) * b
+    }
+}
+
+/// Extended Euclid: returns `(g, x, y)` with `a * x + b * y == g == gcd(a, b)`.
+#[must_use]
+pub fn extended_gcd(a: i64, b: i64) -> (i64, i64, i64) {
+    let (mut old_r, mut r) = (a, b);
+    let (mut old_x, mut x) = (1, 0);
+    let (mut old_y, mut y) = (0, 1);
+    while r != 0 {
+        let q = old_r / r;
+        (old_r, r) = (r, old_r - q * r);
+        (old_x, x) = (x, old_x - q * x);
+        (old_y, y) = (y, old_y - q * y);
+    }
+    if old_r < 0 {
+        (-old_r, -old_x, -old_y)
+    } else {
+        (old_r, old_x, old_y)
+    }
+}
+
+/// The inverse of `a` modulo `m`, if `a` and `m` are coprime.
+#[must_use]
+pub fn mod_inverse(a: i64, m: i64) -> Option<i64> {
+    let (g, x, _) = extended_gcd(a.rem_euclid(m), m);
+    (g == 1).then(|| x.rem_euclid(m))
+}
+
+/// `base.pow(exp) % m` without overflowing intermediate products.
+#[must_use]
+pub fn mod_pow(base: u64, mut exp: u64, m: u64) -> u64 {
+    if m == 1 {
+        return 0;
+    }
+    let m = u128::from(m);
+    let mut base = u128::from(base) % m;
+    let mut result = 1;
+    while exp > 0 {
+        if exp & 1 == 1 {
+            result = result * base % m;
+        }
+        base = base * base % m;
+        exp >>= 1;
+    }
+    u64::try_from(result).expect("result is reduced modulo a u64")
+}
+
+/// Chinese remainder theorem over `(residue, modulus)` pairs, which need not
+/// be coprime. Returns `(x, lcm)` with `0 <= x < lcm` satisfying every
+/// congruence, or `None` if they are inconsistent.
+#[must_use]
+pub fn crt(congruences: &[(i64, i64)]) -> Option<(i64, i64)> {
+    let mut x: i128 = 0;
+    let mut m: i128 = 1;
+    for &(r, n) in congruences {
+        let n = i128::from(n);
+        let r = i128::from(r).rem_euclid(n);
+        let (g, p, _) = extended_gcd(i64::try_from(m).ok()?, i64::try_from(n).ok()?);
+        let g = i128::from(g);
+        if (r - x) % g != 0 {
+            return None;
+        }
+        let step = n / g;
+        let k = ((r - x) / g % step * i128::from(p)).rem_euclid(step);
+        x += m * k;
+        m *= step;
+        x = x.rem_euclid(m);
+    }
+    Some((i64::try_from(x).ok()?, i64::try_from(m).ok()?))
+}
+
+/// Every divisor of `n`, in ascending order.
+#[must_use]
+pub fn divisors(n: usize) -> Vec<usize> {
+    let mut small = Vec::new();
+    let mut large = Vec::new();
+    let mut d = 1;
+    while d * d <= n {
+        if n.is_multiple_of(d) {
+            small.push(d);
+            if d * d != n {
+                large.push(n / d);
+            }
+        }
+        d += 1;
+    }
+    small.extend(large.into_iter().rev());
+    small
+}
+
+/// `10.pow(exp)`, or `None` on overflow.
+#[must_use]
+pub fn pow10(exp: u32) -> Option<usize> {
+    10usize.checked_pow(exp)
+}
+
+/// Number of base-10 digits in `n`. Zero has one digit.
+#[must_use]
+pub fn digit_count(n: usize) -> u32 {
+    n.checked_ilog10().map_or(1, |l| l + 1)
+}
+
+/// Base-10 digits of `n`, least significant first.
+pub fn digits_le(mut n: usize) -> impl Iterator<Item = u8> {
+    let mut done = false;
+    std::iter::from_fn(move || {
+        if done {
+            return None;
+        }
+        let d = u8::try_from(n % 10).expect("a single digit fits in a u8");
+        n /= 10;
+        done = n == 0;
+        Some(d)
+    })
+}
+
+/// Assemble a number from base-10 digits, most significant first.
+pub fn from_digits<I: IntoIterator<Item = u8>>(digits: I) -> usize {
+    digits
+        .into_iter()
+        .fold(0, |acc, d| acc * 10 + usize::from(d))
+}
+
+/// The multiplier that repeats a `len`-digit block `reps` times, i.e.
+/// `(10^(len * reps) - 1) / (10^len - 1)`: `repunit(2, 3) == 10101`.
+/// Returns `None` on overflow.
+#[must_use]
+pub fn repunit(len: u32, reps: u32) -> Option<usize> {
+    let block = pow10(len)?;
+    (1..reps).try_fold(1usize, |acc, _| acc.checked_mul(block)?.checked_add(1))
+}
+
+/// Repeat the `len`-digit `pattern` `reps` times: `repeat_digits(12, 2, 3) == 121212`.
+/// Returns `None` if `pattern` does not have exactly `len` digits or on overflow.
+#[must_use]
+pub fn repeat_digits(pattern: usize, len: u32, reps: u32) -> Option<usize> {
+    if digit_count(pattern) != len {
+        return None;
+    }
+    pattern.checked_mul(repunit(len, reps)?)
+}
+
+/// Whether the digits of `n` are a single block repeated exactly `reps` times.
+#[must_use]
+pub fn is_repetition(n: usize, reps: u32) -> bool {
+    let digits = digit_count(n);
+    reps > 0
+        && digits.is_multiple_of(reps)
+        && repunit(digits / reps, reps).is_some_and(|r| n.is_multiple_of(r))
+}
+
+/// Whether the digits of `n` are some block repeated at least twice.
+#[must_use]
+pub fn is_repeated(n: usize) -> bool {
+    (2..=digit_count(n)).any(|reps| is_repetition(n, reps))
+}
+
+#[cfg(test)]
+#[allow(clippy::unreadable_literal)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn gcd_lcm() {
+        assert_eq!(gcd(12, 18), 6);
+        assert_eq!(gcd(0, 7), 7);
+        assert_eq!(lcm(4, 6), 12);
+        assert_eq!(lcm(0, 6), 0);
+    }
+
+    #[test]
+    fn extended() {
+        for (a, b) in [(240, 46), (-7, 3), (0, 5), (17, -5)] {
+            let (g, x, y) = extended_gcd(a, b);
+            assert_eq!(a * x + b * y, g);
+            assert_eq!(
+                g.unsigned_abs() as usize,
+                gcd(a.unsigned_abs() as usize, b.unsigned_abs() as usize)
+            );
+        }
+        assert_eq!(mod_inverse(3, 11), Some(4));
+        assert_eq!(mod_inverse(-3, 11), Some(7));
+        assert_eq!(mod_inverse(6, 9), None);
+    }
+
+    #[test]
+    fn modular_pow() {
+        assert_eq!(mod_pow(2, 10, 1000), 24);
+        assert_eq!(mod_pow(3, 0, 7), 1);
+        assert_eq!(mod_pow(5, 3, 1), 0);
+        assert_eq!(mod_pow(u64::MAX, 2, u64::MAX - 1), 1);
+    }
+
+    #[test]
+    fn chinese_remainder() {
+        assert_eq!(crt(&[(2, 3), (3, 5), (2, 7)]), Some((23, 105)));
+        assert_eq!(crt(&[(2, 4), (4, 6)]), Some((10, 12)));
+        assert_eq!(crt(&[(1, 4), (2, 6)]), None);
+        assert_eq!(crt(&[]), Some((0, 1)));
+    }
+
+    #[test]
+    fn divisor_lists() {
+        assert_eq!(divisors(1), [1]);
+        assert_eq!(divisors(12), [1, 2, 3, 4, 6, 12]);
+        assert_eq!(divisors(49), [1, 7, 49]);
+    }
+
+    #[test]
+    fn digit_helpers() {
+        assert_eq!(digit_count(0), 1);
+        assert_eq!(digit_count(9), 1);
+        assert_eq!(digit_count(10), 2);
+        assert_eq!(digit_count(usize::MAX), usize::MAX.to_string().len() as u32);
+        assert_eq!(digits_le(0).collect::<Vec<_>>(), [0]);
+        assert_eq!(digits_le(1203).collect::<Vec<_>>(), [3, 0, 2, 1]);
+        assert_eq!(from_digits([1, 2, 0, 3]), 1203);
+    }
+
+    #[test]
+    fn repetitions() {
+        assert_eq!(repunit(1, 3), Some(111));
+        assert_eq!(repunit(2, 3), Some(10101));
+        assert_eq!(repunit(3, 1), Some(1));
+        assert_eq!(repunit(10, 3), None);
+        assert_eq!(repeat_digits(12, 2, 3), Some(121212));
+        assert_eq!(repeat_digits(5, 2, 3), None);
+        assert!(is_repetition(1212, 2));
+        assert!(!is_repetition(1212, 4));
+        assert!(is_repetition(1111, 4));
+        assert!(!is_repetition(1213, 2));
+        assert!(!is_repetition(12, 2));
+        for n in [
+            11,
+            111,
+            1111,
+            12_12_12_12_12,
+            123_123_123_123,
+            1234_1234_1234_1234,
+        ] {
+            assert!(is_repeated(n), "n = {n}");
+        }
+        for n in [
+            10,
+            101,
+            1011,
+            11_12_12_12_12,
+            113_123_123_123,
+            1134_1234_1234_1234,
+        ] {
+            assert!(!is_repeated(n), "n = {n}");
+        }
+        assert!(is_repeated(1234_5678_1234_5678));
+        assert!(!is_repeated(1134_5678_1234_5678));
+        for n in 1..100_000 {
+            let s = n.to_string();
+            let expected =
+                (2..=s.len()).any(|k| s.len().is_multiple_of(k) && s[..s.len() / k].repeat(k) == s);
+            assert_eq!(is_repeated(n), expected, "n = {n}");
+        }
+    }
+}
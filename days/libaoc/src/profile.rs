@@ -0,0 +1,323 @@
+//! Named accounts, so several people's inputs can be downloaded and cached
+//! side by side.
+//!
+//! Profiles live in a TOML file (`.aoc/profiles.toml`, or `$AOC_CONFIG`):
+//!
+//! ```toml
+//! # Used when no profile is named.
+//! default = "alex"
+//!
+//! [profiles.alex]
+//! session = "53616c7465645f5f..."
+//!
+//! [profiles.sam]
+//! session_file = "~/.config/aoc/sam"
+//!
+//! [profiles.kim]
+//! session_cmd = "pass show aoc/kim"
+//! ```
+//!
+//! A leading `~/` in `session_file` is expanded with `$HOME`. Token files,
+//! and this file if it holds tokens itself, must not be world-readable.
+//! Without a profile the token comes from the environment; see
+//! [`session::from_env`].
+use std::collections::BTreeMap;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+
+use serde::Deserialize;
+use thiserror::Error;
+
+use crate::session::{self, Session, SessionError};
+
+const CONFIG_LOCATION: &str = "./.aoc/profiles.toml";
+
+#[derive(Debug, Error)]
+pub enum ProfileError {
+    #[error("failed to read {path}: {source}")]
+    Read { path: PathBuf, source: io::Error },
+    /// Only the parser's message is kept: its source snippet may hold a token.
+    #[error("invalid profile config {path}: {message}")]
+    Parse { path: PathBuf, message: String },
+    #[error("profile name {name:?} in {path} must be letters, digits, '-' or '_'")]
+    InvalidName { name: String, path: PathBuf },
+    #[error("no profile named {name:?} in {path}")]
+    Unknown { name: String, path: PathBuf },
+    #[error("profile {name:?} needs exactly one of session, session_file or session_cmd")]
+    SessionSource { name: String },
+    #[error(
+        "no session: set AOC_SESSION_CMD, AOC_SESSION_FILE or SESSION, or add a profile to {0}"
+    )]
+    NoSession(PathBuf),
+    #[error(transparent)]
+    Session(#[from] SessionError),
+}
+
+/// An account to fetch inputs with.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Profile {
+    name: Option<String>,
+    session: Session,
+}
+
+impl Profile {
+    /// A profile for `session`; unnamed profiles use the un-namespaced cache.
+    #[must_use]
+    pub fn new(name: Option<String>, session: Session) -> Self {
+        Self { name, session }
+    }
+
+    #[must_use]
+    pub fn name(&self) -> Option<&str> {
+        self.name.as_deref()
+    }
+
+    #[must_use]
+    pub fn session(&self) -> &Session {
+        &self.session
+    }
+}
+
+impl fmt::Debug for Profile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Profile")
+            .field("name", &self.name)
+            .field("session", &self.session)
+            .finish()
+    }
+}
+
+/// Where one profile's token comes from.
+#[derive(Default, Deserialize)]
+#[serde(deny_unknown_fields)]
+struct Entry {
+    session: Option<String>,
+    session_file: Option<PathBuf>,
+    session_cmd: Option<String>,
+}
+
+impl fmt::Debug for Entry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("Entry")
+            .field("session", &self.session.as_ref().map(|_| "[redacted]"))
+            .field("session_file", &self.session_file)
+            .field("session_cmd", &self.session_cmd)
+            .finish()
+    }
+}
+
+/// The contents of the profiles file.
+#[derive(Debug, Default, Deserialize)]
+pub struct Config {
+    default: Option<String>,
+    #[serde(default)]
+    profiles: BTreeMap<String, Entry>,
+    #[serde(skip)]
+    path: PathBuf,
+}
+
+impl Config {
+    /// Read the config at `path`. A missing file is an empty config.
+    ///
+    /// # Errors
+    /// If the file exists but cannot be read or parsed, or holds tokens and
+    /// is world-readable.
+    pub fn load(path: &Path) -> Result<Self, ProfileError> {
+        let text = match std::fs::read_to_string(path) {
+            Ok(text) => text,
+            Err(e) if e.kind() == io::ErrorKind::NotFound => String::new(),
+            Err(source) => {
+                return Err(ProfileError::Read {
+                    path: path.into(),
+                    source,
+                });
+            }
+        };
+        let config = Self::parse(&text, path)?;
+        if config
+            .profiles
+            .values()
+            .any(|entry| entry.session.is_some())
+        {
+            session::check_permissions(path)?;
+        }
+        Ok(config)
+    }
+
+    fn parse(text: &str, path: &Path) -> Result<Self, ProfileError> {
+        let mut config: Self = toml::from_str(text).map_err(|e| ProfileError::Parse {
+            path: path.into(),
+            message: e.message().to_owned(),
+        })?;
+        config.path = path.into();
+        // Names become cache directories, so keep them to one path component.
+        let valid = |name: &str| {
+            !name.is_empty()
+                && name
+                    .bytes()
+                    .all(|b| b.is_ascii_alphanumeric() || b == b'-' || b == b'_')
+        };
+        if let Some(name) = config.profiles.keys().find(|name| !valid(name)) {
+            return Err(ProfileError::InvalidName {
+                name: name.clone(),
+                path: config.path,
+            });
+        }
+        Ok(config)
+    }
+
+    /// The profile called `name`, else the configured default, else an
+    /// unnamed profile with the token from [`session::from_env`].
+    ///
+    /// # Errors
+    /// If `name` (or the default) is not configured, its token cannot be
+    /// read, or nothing names a session.
+    pub fn profile(&self, name: Option<&str>) -> Result<Profile, ProfileError> {
+        match name.or(self.default.as_deref()) {
+            Some(name) => self.named(name),
+            None => session::from_env()?
+                .map(|session| Profile::new(None, session))
+                .ok_or_else(|| ProfileError::NoSession(self.path.clone())),
+        }
+    }
+
+    fn named(&self, name: &str) -> Result<Profile, ProfileError> {
+        let entry = self
+            .profiles
+            .get(name)
+            .ok_or_else(|| ProfileError::Unknown {
+                name: name.into(),
+                path: self.path.clone(),
+            })?;
+        let session = match (&entry.session, &entry.session_file, &entry.session_cmd) {
+            (Some(token), None, None) => Session::new(token, &format!("profile {name:?}"))?,
+            (None, Some(path), None) => session::from_file(path)?,
+            (None, None, Some(command)) => session::from_command(command)?,
+            _ => return Err(ProfileError::SessionSource { name: name.into() }),
+        };
+        Ok(Profile::new(Some(name.into()), session))
+    }
+
+    /// The name of every configured profile, in order. Their tokens are only
+    /// read when a profile is selected.
+    pub fn names(&self) -> impl Iterator<Item = &str> {
+        self.profiles.keys().map(String::as_str)
+    }
+}
+
+/// Where the profiles file is read from: `$AOC_CONFIG`, else `.aoc/profiles.toml`.
+#[must_use]
+pub fn config_path() -> PathBuf {
+    std::env::var_os("AOC_CONFIG").map_or_else(|| PathBuf::from(CONFIG_LOCATION), PathBuf::from)
+}
+
+/// Resolve `name`, falling back to `$AOC_PROFILE`, the configured default,
+/// and finally `SESSION`. Loads `.env` first.
+///
+/// # Errors
+/// See [`Config::load`] and [`Config::profile`].
+pub fn resolve(name: Option<&str>) -> Result<Profile, ProfileError> {
+    dotenv::dotenv().ok();
+    let env_name = std::env::var("AOC_PROFILE").ok();
+    Config::load(&config_path())?.profile(name.or(env_name.as_deref()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const CONFIG: &str = r#"
+        default = "alex"
+
+        [profiles.alex]
+        session = "aaa"
+
+        [profiles.sam]
+        session = "bbb"
+    "#;
+
+    #[test]
+    fn selects_profiles() {
+        let config = Config::parse(CONFIG, Path::new("profiles.toml")).unwrap();
+        let sam = config.profile(Some("sam")).unwrap();
+        assert_eq!((sam.name(), sam.session().expose()), (Some("sam"), "bbb"));
+        let default = config.profile(None).unwrap();
+        assert_eq!(
+            (default.name(), default.session().expose()),
+            (Some("alex"), "aaa")
+        );
+        assert_eq!(
+            config.profile(Some("kim")).unwrap_err().to_string(),
+            r#"no profile named "kim" in profiles.toml"#
+        );
+        assert_eq!(config.names().collect::<Vec<_>>(), ["alex", "sam"]);
+    }
+
+    #[test]
+    fn session_sources() {
+        let config = Config::parse(
+            r#"
+            [profiles.cmd]
+            session_cmd = "echo from-cmd"
+            [profiles.both]
+            session = "a"
+            session_cmd = "echo b"
+            [profiles.none]
+            "#,
+            Path::new("p.toml"),
+        )
+        .unwrap();
+        let cmd = config.profile(Some("cmd")).unwrap();
+        assert_eq!(cmd.session().expose(), "from-cmd");
+        for name in ["both", "none"] {
+            assert!(matches!(
+                config.profile(Some(name)),
+                Err(ProfileError::SessionSource { .. })
+            ));
+        }
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn inline_tokens_need_a_private_file() {
+        use std::os::unix::fs::PermissionsExt;
+        let path = std::env::temp_dir().join(format!("libaoc-profiles-{}", std::process::id()));
+        std::fs::write(&path, CONFIG).unwrap();
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o644)).unwrap();
+        assert!(matches!(
+            Config::load(&path),
+            Err(ProfileError::Session(SessionError::WorldReadable { .. }))
+        ));
+        std::fs::set_permissions(&path, std::fs::Permissions::from_mode(0o600)).unwrap();
+        assert!(Config::load(&path).is_ok());
+        std::fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn missing_and_invalid_configs() {
+        let missing = Config::load(Path::new("./no/such/profiles.toml")).unwrap();
+        assert_eq!(missing.names().count(), 0);
+        let error = Config::parse(
+            "[profiles.alex]\nsession = \"secret\"\nx = 1\n",
+            Path::new("p.toml"),
+        )
+        .unwrap_err();
+        assert!(matches!(error, ProfileError::Parse { .. }));
+        assert!(!error.to_string().contains("secret"), "{error}");
+        assert!(matches!(
+            Config::parse(
+                "[profiles.\"../x\"]\nsession = \"s\"\n",
+                Path::new("p.toml")
+            ),
+            Err(ProfileError::InvalidName { .. })
+        ));
+    }
+
+    #[test]
+    fn debug_hides_session() {
+        let session = Session::new("secret", "test").unwrap();
+        let profile = Profile::new(Some("alex".into()), session);
+        assert!(!format!("{profile:?}").contains("secret"));
+    }
+}
@@ -0,0 +1,244 @@
+//! Every solution linked into a binary, registered by the day crates
+//! themselves with [`aoc`](crate::aoc):
+//!
+//! ```ignore
+//! #[aoc(part = 1)]
+//! pub fn try_part_1(input: &str) -> Result<usize, InputError> { .. }
+//! ```
+//!
+//! Only crates that are linked in are seen, so a binary must use each day
+//! crate it wants (e.g. `use day05 as _;`). Functions still `todo!()`, as
+//! copied from the template, register their day and nothing else.
+use std::collections::BTreeMap;
+
+#[doc(hidden)]
+pub use inventory;
+use thiserror::Error;
+
+use crate::InputError;
+use crate::grammar::Grammar;
+use crate::manifest::{self, ManifestError, Puzzle};
+
+/// One part's fallible solver.
+pub type Solver = fn(&str) -> Result<usize, InputError>;
+
+/// A day's seeded input generator: `(seed, size) -> input`.
+pub type Generator = fn(u64, usize) -> String;
+
+#[derive(Debug, Error)]
+pub enum RegistryError {
+    #[error("{name}: {source}")]
+    Manifest {
+        name: &'static str,
+        source: ManifestError,
+    },
+    #[error("{name}: #[aoc] says {field} {attribute}, but the crate is for {puzzle}")]
+    Mismatch {
+        name: &'static str,
+        field: &'static str,
+        attribute: u16,
+        puzzle: Puzzle,
+    },
+    #[error("{name}: {puzzle} already has a {what}, {other}")]
+    Duplicate {
+        name: &'static str,
+        other: &'static str,
+        puzzle: Puzzle,
+        what: &'static str,
+    },
+}
+
+/// What a registered function is.
+#[derive(Clone, Copy)]
+pub enum Kind {
+    Part(u8, Solver),
+    Grammar(fn() -> Grammar),
+    Generator(Generator),
+}
+
+/// One `#[aoc]` function. Built by the attribute; not meant to be written by hand.
+pub struct Entry {
+    /// The function's path, for error messages.
+    pub name: &'static str,
+    /// The defining crate's `Cargo.toml`.
+    pub manifest: &'static str,
+    pub year: Option<u16>,
+    pub day: Option<u8>,
+    pub kind: Kind,
+    /// Whether the function is still `todo!()`. Its day is registered, but it
+    /// is not.
+    pub todo: bool,
+}
+
+inventory::collect!(Entry);
+
+impl Entry {
+    /// The puzzle this entry belongs to, from its attribute and manifest.
+    fn puzzle(&self) -> Result<Puzzle, RegistryError> {
+        let from_manifest = manifest::puzzle(self.manifest);
+        let puzzle = match (self.year, self.day, from_manifest) {
+            (Some(year), Some(day), Err(_)) => return Ok(Puzzle { year, day }),
+            (_, _, Err(source)) => {
+                return Err(RegistryError::Manifest {
+                    name: self.name,
+                    source,
+                });
+            }
+            (_, _, Ok(puzzle)) => puzzle,
+        };
+        let mismatch = |field, attribute| RegistryError::Mismatch {
+            name: self.name,
+            field,
+            attribute,
+            puzzle,
+        };
+        match (self.year, self.day) {
+            (Some(year), _) if year != puzzle.year => Err(mismatch("year", year)),
+            (_, Some(day)) if day != puzzle.day => Err(mismatch("day", day.into())),
+            _ => Ok(puzzle),
+        }
+    }
+}
+
+/// Everything registered for one day.
+#[derive(Clone, Copy, Default)]
+pub struct Day {
+    pub parts: [Option<Solver>; 2],
+    pub grammar: Option<fn() -> Grammar>,
+    pub generator: Option<Generator>,
+}
+
+impl Day {
+    /// Both solvers, if both parts are registered.
+    #[must_use]
+    pub fn solvers(&self) -> Option<[Solver; 2]> {
+        Some([self.parts[0]?, self.parts[1]?])
+    }
+}
+
+/// Every registered day, by puzzle.
+pub type Registry = BTreeMap<Puzzle, Day>;
+
+/// Collect every [`Entry`] linked into this binary.
+///
+/// # Errors
+/// If an entry's puzzle cannot be determined, or two entries fill the same slot.
+pub fn collect() -> Result<Registry, RegistryError> {
+    build(inventory::iter::<Entry>)
+}
+
+fn build<'a>(entries: impl IntoIterator<Item = &'a Entry>) -> Result<Registry, RegistryError> {
+    // The first entry in each slot, kept for error messages.
+    let mut names: BTreeMap<(Puzzle, &str), &str> = BTreeMap::new();
+    let mut registry = Registry::new();
+    for entry in entries {
+        let puzzle = entry.puzzle()?;
+        let what = match entry.kind {
+            Kind::Part(1, _) => "part 1",
+            Kind::Part(..) => "part 2",
+            Kind::Grammar(_) => "grammar",
+            Kind::Generator(_) => "generator",
+        };
+        if let Some(other) = names.insert((puzzle, what), entry.name) {
+            return Err(RegistryError::Duplicate {
+                name: entry.name,
+                other,
+                puzzle,
+                what,
+            });
+        }
+        let day = registry.entry(puzzle).or_default();
+        if entry.todo {
+            continue;
+        }
+        match entry.kind {
+            Kind::Part(part, solver) => day.parts[usize::from(part - 1)] = Some(solver),
+            Kind::Grammar(grammar) => day.grammar = Some(grammar),
+            Kind::Generator(generator) => day.generator = Some(generator),
+        }
+    }
+    Ok(registry)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::aoc;
+
+    #[aoc(year = 2015, day = 25, part = 1)]
+    fn registered(input: &str) -> Result<usize, InputError> {
+        Ok(input.len())
+    }
+
+    fn entry(name: &'static str, manifest: &'static str, day: Option<u8>, kind: Kind) -> Entry {
+        Entry {
+            name,
+            manifest,
+            year: None,
+            day,
+            kind,
+            todo: false,
+        }
+    }
+
+    const DAY01: &str = "[package]\nname = \"aoc2024-day01\"\n";
+
+    #[test]
+    fn collects_registered_functions() {
+        let registry = collect().unwrap();
+        let day = registry[&Puzzle {
+            year: 2015,
+            day: 25,
+        }];
+        assert_eq!(day.parts[0].unwrap()("abc"), Ok(3));
+        assert!(day.parts[1].is_none() && day.solvers().is_none());
+    }
+
+    #[test]
+    fn builds_days() {
+        let entries = [
+            entry("one", DAY01, None, Kind::Part(1, registered)),
+            entry("two", DAY01, Some(1), Kind::Part(2, registered)),
+            entry("gen", DAY01, None, Kind::Generator(|_, _| String::new())),
+        ];
+        let registry = build(&entries).unwrap();
+        let day = registry[&Puzzle { year: 2024, day: 1 }];
+        assert!(day.solvers().is_some() && day.generator.is_some() && day.grammar.is_none());
+    }
+
+    #[test]
+    fn registers_days_but_not_todos() {
+        let todo = Entry {
+            todo: true,
+            ..entry("gen", DAY01, None, Kind::Generator(|_, _| todo!()))
+        };
+        let registry = build([&todo]).unwrap();
+        let day = registry[&Puzzle { year: 2024, day: 1 }];
+        assert!(day.generator.is_none() && day.solvers().is_none() && day.parts[0].is_none());
+    }
+
+    #[test]
+    fn rejects_conflicts() {
+        let error = |entries: &[Entry]| build(entries).err().unwrap().to_string();
+        assert_eq!(
+            error(&[entry("two", DAY01, Some(2), Kind::Part(2, registered))]),
+            "two: #[aoc] says day 2, but the crate is for 2024 day 1"
+        );
+        assert_eq!(
+            error(&[
+                entry("a", DAY01, None, Kind::Part(1, registered)),
+                entry("b", DAY01, None, Kind::Part(1, registered)),
+            ]),
+            "b: 2024 day 1 already has a part 1, a"
+        );
+        assert!(
+            error(&[entry(
+                "x",
+                "[package]\nname = \"x\"\n",
+                None,
+                Kind::Part(1, registered)
+            )])
+            .starts_with("x: x: no year")
+        );
+    }
+}
@@ -0,0 +1,66 @@
+use std::ops::Range;
+
+/// A small seeded PRNG (SplitMix64) for generating reproducible puzzle inputs.
+/// Not suitable for anything security related.
+#[derive(Clone, Debug)]
+pub struct Rng(u64);
+
+impl Rng {
+    #[must_use]
+    pub fn new(seed: u64) -> Self {
+        Self(seed)
+    }
+
+    pub fn next_u64(&mut self) -> u64 {
+        self.0 = self.0.wrapping_add(0x9E37_79B9_7F4A_7C15);
+        let mut z = self.0;
+        z = (z ^ (z >> 30)).wrapping_mul(0xBF58_476D_1CE4_E5B9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94D0_49BB_1331_11EB);
+        z ^ (z >> 31)
+    }
+
+    /// A uniform value in `range`.
+    ///
+    /// # Panics
+    /// If `range` is empty.
+    pub fn range(&mut self, range: Range<usize>) -> usize {
+        assert!(!range.is_empty(), "cannot sample from an empty range");
+        let span = (range.end - range.start) as u64;
+        // Multiply-shift maps 64 random bits onto the span with negligible bias.
+        let offset = (u128::from(self.next_u64()) * u128::from(span)) >> 64;
+        range.start + offset as usize
+    }
+
+    /// `true` with probability `p`.
+    pub fn chance(&mut self, p: f64) -> bool {
+        let unit = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+        unit < p
+    }
+
+    /// A uniformly chosen element of `items`.
+    ///
+    /// # Panics
+    /// If `items` is empty.
+    pub fn pick<'a, T>(&mut self, items: &'a [T]) -> &'a T {
+        &items[self.range(0..items.len())]
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn seeded_and_in_range() {
+        let a: Vec<_> = (0..100)
+            .scan(Rng::new(7), |r, _| Some(r.range(3..10)))
+            .collect();
+        let b: Vec<_> = (0..100)
+            .scan(Rng::new(7), |r, _| Some(r.range(3..10)))
+            .collect();
+        assert_eq!(a, b);
+        assert!(a.iter().all(|x| (3..10).contains(x)));
+        assert!((3..10).all(|x| a.contains(&x)));
+        assert_ne!(Rng::new(1).next_u64(), Rng::new(2).next_u64());
+    }
+}
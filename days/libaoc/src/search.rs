@@ -0,0 +1,332 @@
+use std::cmp::Reverse;
+use std::collections::{BinaryHeap, HashMap, HashSet, VecDeque};
+use std::hash::Hash;
+
+use crate::grid::{Grid, Pos};
+
+/// Distances and predecessors found by searching outward from a single start node.
+#[derive(Clone, Debug)]
+pub struct Paths<N> {
+    order: Vec<N>,
+    nodes: HashMap<N, (usize, Option<N>)>,
+}
+
+impl<N: Clone + Eq + Hash> Paths<N> {
+    fn new(start: N) -> Self {
+        Self {
+            order: Vec::new(),
+            nodes: HashMap::from([(start, (0, None))]),
+        }
+    }
+
+    /// Distance from the start to `node`, or `None` if it was never reached.
+    #[must_use]
+    pub fn distance(&self, node: &N) -> Option<usize> {
+        self.nodes.get(node).map(|&(d, _)| d)
+    }
+
+    /// The path from the start to `node`, inclusive of both ends.
+    #[must_use]
+    pub fn path_to(&self, node: &N) -> Option<Vec<N>> {
+        let mut path = vec![node.clone()];
+        let mut prev = &self.nodes.get(node)?.1;
+        while let Some(p) = prev {
+            path.push(p.clone());
+            prev = &self.nodes[p].1;
+        }
+        path.reverse();
+        Some(path)
+    }
+
+    /// Nodes in the order the search settled them.
+    #[must_use]
+    pub fn order(&self) -> &[N] {
+        &self.order
+    }
+
+    /// Every reached node with its distance from the start.
+    pub fn distances(&self) -> impl Iterator<Item = (&N, usize)> {
+        self.nodes.iter().map(|(n, &(d, _))| (n, d))
+    }
+}
+
+/// Breadth-first search over every node reachable from `start`.
+pub fn bfs<N, F, I>(start: N, successors: F) -> Paths<N>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    bfs_inner(start, successors, |_| false)
+}
+
+/// Breadth-first search that stops at the first node satisfying `goal`,
+/// returning the shortest path to it.
+pub fn bfs_to<N, F, I, G>(start: N, successors: F, mut goal: G) -> Option<Vec<N>>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+    G: FnMut(&N) -> bool,
+{
+    let paths = bfs_inner(start, successors, &mut goal);
+    paths.order.last().filter(|n| goal(n))?;
+    paths.path_to(paths.order.last()?)
+}
+
+fn bfs_inner<N, F, I, G>(start: N, mut successors: F, mut stop: G) -> Paths<N>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+    G: FnMut(&N) -> bool,
+{
+    let mut paths = Paths::new(start.clone());
+    let mut queue = VecDeque::from([start]);
+    while let Some(node) = queue.pop_front() {
+        paths.order.push(node.clone());
+        if stop(&node) {
+            break;
+        }
+        let dist = paths.nodes[&node].0 + 1;
+        for next in successors(&node) {
+            if !paths.nodes.contains_key(&next) {
+                paths.nodes.insert(next.clone(), (dist, Some(node.clone())));
+                queue.push_back(next);
+            }
+        }
+    }
+    paths
+}
+
+/// Depth-first search over every node reachable from `start`.
+///
+/// Distances are depths in the DFS tree rather than shortest distances, and
+/// successors are explored in the order they are yielded.
+pub fn dfs<N, F, I>(start: N, mut successors: F) -> Paths<N>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = N>,
+{
+    let mut paths = Paths::new(start.clone());
+    let mut stack = vec![(start, 0, None)];
+    let mut seen = HashSet::new();
+    while let Some((node, depth, parent)) = stack.pop() {
+        if !seen.insert(node.clone()) {
+            continue;
+        }
+        paths.nodes.insert(node.clone(), (depth, parent));
+        paths.order.push(node.clone());
+        let next: Vec<N> = successors(&node).into_iter().collect();
+        for n in next.into_iter().rev() {
+            if !seen.contains(&n) {
+                stack.push((n, depth + 1, Some(node.clone())));
+            }
+        }
+    }
+    paths
+}
+
+/// Dijkstra's algorithm over every node reachable from `start`, where
+/// `successors` yields `(node, edge_cost)` pairs.
+pub fn dijkstra<N, F, I>(start: N, successors: F) -> Paths<N>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, usize)>,
+{
+    best_first(start, successors, |_| 0, |_| false)
+}
+
+/// A* search from `start` to the first node satisfying `goal`, returning the
+/// path and its cost. `heuristic` must never overestimate the remaining cost.
+pub fn astar<N, F, I, H, G>(
+    start: N,
+    successors: F,
+    heuristic: H,
+    mut goal: G,
+) -> Option<(Vec<N>, usize)>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, usize)>,
+    H: FnMut(&N) -> usize,
+    G: FnMut(&N) -> bool,
+{
+    let paths = best_first(start, successors, heuristic, &mut goal);
+    let end = paths.order.last().filter(|n| goal(n))?;
+    Some((paths.path_to(end)?, paths.distance(end)?))
+}
+
+fn best_first<N, F, I, H, G>(start: N, mut successors: F, mut heuristic: H, mut stop: G) -> Paths<N>
+where
+    N: Clone + Eq + Hash,
+    F: FnMut(&N) -> I,
+    I: IntoIterator<Item = (N, usize)>,
+    H: FnMut(&N) -> usize,
+    G: FnMut(&N) -> bool,
+{
+    let mut paths = Paths::new(start.clone());
+    // Nodes need not be `Ord`, so the heap holds indices into `pending`.
+    let mut pending = vec![start];
+    let mut heap = BinaryHeap::from([Reverse((heuristic(&pending[0]), 0, 0))]);
+    let mut settled = HashSet::new();
+    while let Some(Reverse((_, dist, idx))) = heap.pop() {
+        let node = pending[idx].clone();
+        if paths.nodes[&node].0 < dist || !settled.insert(node.clone()) {
+            continue;
+        }
+        paths.order.push(node.clone());
+        if stop(&node) {
+            break;
+        }
+        for (next, cost) in successors(&node) {
+            let next_dist = dist + cost;
+            if paths.distance(&next).is_none_or(|d| next_dist < d) {
+                paths
+                    .nodes
+                    .insert(next.clone(), (next_dist, Some(node.clone())));
+                heap.push(Reverse((
+                    next_dist + heuristic(&next),
+                    next_dist,
+                    pending.len(),
+                )));
+                pending.push(next);
+            }
+        }
+    }
+    paths
+}
+
+/// Breadth-first search across a grid, stepping orthogonally onto cells for
+/// which `passable` holds.
+pub fn grid_bfs<P>(grid: &Grid, start: Pos, passable: P) -> Paths<Pos>
+where
+    P: Fn(u8) -> bool,
+{
+    bfs(start, |&p| {
+        grid.neighbors4(p)
+            .filter(|&q| passable(grid[q]))
+            .collect::<Vec<_>>()
+    })
+}
+
+/// Shortest orthogonal path across a grid from `start` to `end`, stepping only
+/// onto cells for which `passable` holds.
+pub fn grid_shortest_path<P>(grid: &Grid, start: Pos, end: Pos, passable: P) -> Option<Vec<Pos>>
+where
+    P: Fn(u8) -> bool,
+{
+    astar(
+        start,
+        |&p| {
+            grid.neighbors4(p)
+                .filter(|&q| passable(grid[q]))
+                .map(|q| (q, 1))
+                .collect::<Vec<_>>()
+        },
+        |&(r, c)| r.abs_diff(end.0) + c.abs_diff(end.1),
+        |&p| p == end,
+    )
+    .map(|(path, _)| path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const MAZE: &str = "\
+S.#.....
+.##.###.
+....#...
+.##...#E
+";
+
+    fn open(b: u8) -> bool {
+        b != b'#'
+    }
+
+    #[test]
+    fn bfs_maze() {
+        let grid = Grid::from(MAZE);
+        let start = grid.find(b'S').unwrap();
+        let end = grid.find(b'E').unwrap();
+        let paths = grid_bfs(&grid, start, open);
+        assert_eq!(paths.distance(&end), Some(12));
+        let path = paths.path_to(&end).unwrap();
+        assert_eq!(path.len(), 13);
+        assert_eq!(path.first(), Some(&start));
+        assert_eq!(path.last(), Some(&end));
+        assert!(
+            path.windows(2)
+                .all(|w| grid.neighbors4(w[0]).any(|n| n == w[1]))
+        );
+        assert_eq!(paths.distance(&(0, 2)), None);
+    }
+
+    #[test]
+    fn bfs_to_stops_at_goal() {
+        let path = bfs_to(0u32, |&n| [n + 1, n * 2], |&n| n == 10).unwrap();
+        assert_eq!(path, [0, 1, 2, 4, 5, 10]);
+        assert_eq!(
+            bfs_to(0u32, |&n| (n < 5).then_some(n + 1), |&n| n == 10),
+            None
+        );
+    }
+
+    #[test]
+    fn astar_matches_bfs() {
+        let grid = Grid::from(MAZE);
+        let path = grid_shortest_path(&grid, (0, 0), (3, 7), open).unwrap();
+        assert_eq!(path.len(), 13);
+        assert_eq!(grid_shortest_path(&grid, (0, 0), (0, 2), open), None);
+    }
+
+    #[test]
+    fn dijkstra_weighted() {
+        // Digits are the cost of stepping onto that cell.
+        let grid = Grid::from(
+            "\
+1911
+1919
+1119
+",
+        );
+        let paths = dijkstra((0, 0), |&p| {
+            grid.neighbors4(p)
+                .map(|q| (q, usize::from(grid[q] - b'0')))
+                .collect::<Vec<_>>()
+        });
+        assert_eq!(paths.distance(&(0, 3)), Some(7));
+        assert_eq!(
+            paths.path_to(&(0, 3)).unwrap(),
+            [
+                (0, 0),
+                (1, 0),
+                (2, 0),
+                (2, 1),
+                (2, 2),
+                (1, 2),
+                (0, 2),
+                (0, 3)
+            ]
+        );
+        assert_eq!(paths.order().first(), Some(&(0, 0)));
+    }
+
+    #[test]
+    fn dfs_visits_everything_once() {
+        let grid = Grid::from(MAZE);
+        let paths = dfs((0, 0), |&p| {
+            grid.neighbors4(p)
+                .filter(|&q| open(grid[q]))
+                .collect::<Vec<_>>()
+        });
+        let open_cells = grid.positions().filter(|&p| open(grid[p])).count();
+        assert_eq!(paths.order().len(), open_cells);
+        let end = grid.find(b'E').unwrap();
+        let path = paths.path_to(&end).unwrap();
+        assert_eq!(paths.distance(&end), Some(path.len() - 1));
+    }
+}
@@ -0,0 +1,273 @@
+//! Where session tokens come from, and keeping them out of any output.
+//!
+//! Without a profile, the token is taken from the first of:
+//! - `AOC_SESSION_CMD`: a shell command printing the token, e.g. a password
+//!   manager CLI
+//! - `AOC_SESSION_FILE`: a file holding the token, which must not be
+//!   world-readable. A leading `~/` is expanded with `$HOME`.
+//! - `SESSION`, usually set through `.env`
+use std::ffi::OsString;
+use std::fmt;
+use std::io;
+use std::path::{Path, PathBuf};
+use std::process::Command;
+use std::sync::LazyLock;
+
+use regex::Regex;
+use thiserror::Error;
+
+const REDACTED: &str = "[redacted]";
+
+#[derive(Debug, Error)]
+pub enum SessionError {
+    #[error("failed to read session file {path}: {source}")]
+    Read { path: PathBuf, source: io::Error },
+    #[error("{path} holds a session token but is world-readable; run `chmod 600 {path}`")]
+    WorldReadable { path: PathBuf },
+    #[error("session command {command:?} failed: {reason}")]
+    Command { command: String, reason: String },
+    #[error("session from {0} is empty")]
+    Empty(String),
+}
+
+/// A session token. Formatting it prints a placeholder; use
+/// [`Session::expose`] where the token itself is needed.
+#[derive(Clone, PartialEq, Eq)]
+pub struct Session(String);
+
+impl Session {
+    /// # Errors
+    /// If `token` is blank; `source` names where it came from.
+    pub fn new(token: &str, source: &str) -> Result<Self, SessionError> {
+        let token = token.trim();
+        if token.is_empty() {
+            return Err(SessionError::Empty(source.into()));
+        }
+        Ok(Self(token.into()))
+    }
+
+    #[must_use]
+    pub fn expose(&self) -> &str {
+        &self.0
+    }
+
+    /// The `Cookie` header value for requests to adventofcode.com.
+    #[must_use]
+    pub fn cookie(&self) -> String {
+        format!("session={}", self.0)
+    }
+
+    /// `text` with this token, and anything that looks like a session cookie,
+    /// replaced by a placeholder.
+    #[must_use]
+    pub fn redact(&self, text: &str) -> String {
+        redact(&text.replace(&self.0, REDACTED))
+    }
+}
+
+impl fmt::Debug for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+impl fmt::Display for Session {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(REDACTED)
+    }
+}
+
+/// `text` with anything that looks like a session cookie replaced.
+#[must_use]
+pub fn redact(text: &str) -> String {
+    static COOKIE: LazyLock<Regex> =
+        LazyLock::new(|| Regex::new(r"session=[^;\s]+").expect("valid regex"));
+    COOKIE
+        .replace_all(text, format!("session={REDACTED}"))
+        .into_owned()
+}
+
+/// Fail if `path` is world-readable. Its group may still read it.
+///
+/// # Errors
+/// If the file's metadata cannot be read or it is world-readable.
+pub fn check_permissions(path: &Path) -> Result<(), SessionError> {
+    #[cfg(unix)]
+    {
+        use std::os::unix::fs::PermissionsExt;
+        let mode = std::fs::metadata(path)
+            .map_err(|source| SessionError::Read {
+                path: path.into(),
+                source,
+            })?
+            .permissions()
+            .mode();
+        if mode & 0o004 != 0 {
+            return Err(SessionError::WorldReadable { path: path.into() });
+        }
+    }
+    Ok(())
+}
+
+/// Read a token from `path`, after checking it is not world-readable. A
+/// leading `~/` is expanded with `$HOME`.
+///
+/// # Errors
+/// If the file is world-readable, unreadable, or blank.
+pub fn from_file(path: &Path) -> Result<Session, SessionError> {
+    let path = &expand_home(path, std::env::var_os("HOME"));
+    check_permissions(path)?;
+    let token = std::fs::read_to_string(path).map_err(|source| SessionError::Read {
+        path: path.into(),
+        source,
+    })?;
+    Session::new(&token, &path.display().to_string())
+}
+
+/// `path` with a leading `~` component replaced by `home`. `~user` and paths
+/// without a `home` are left alone.
+fn expand_home(path: &Path, home: Option<OsString>) -> PathBuf {
+    match (path.strip_prefix("~"), home) {
+        (Ok(rest), Some(home)) if rest.as_os_str().is_empty() => home.into(),
+        (Ok(rest), Some(home)) => PathBuf::from(home).join(rest),
+        _ => path.into(),
+    }
+}
+
+/// Run `command` through the shell and take the token from its output.
+///
+/// # Errors
+/// If the command cannot be run, exits unsuccessfully, or prints nothing.
+pub fn from_command(command: &str) -> Result<Session, SessionError> {
+    let failed = |reason: String| SessionError::Command {
+        command: command.into(),
+        reason,
+    };
+    let output = Command::new("sh")
+        .args(["-c", command])
+        .output()
+        .map_err(|e| failed(e.to_string()))?;
+    // Output is left out of the error: it may be the token.
+    if !output.status.success() {
+        return Err(failed(output.status.to_string()));
+    }
+    let token =
+        String::from_utf8(output.stdout).map_err(|_| failed("output is not UTF-8".into()))?;
+    Session::new(&token, command)
+}
+
+/// The token named by `AOC_SESSION_CMD`, `AOC_SESSION_FILE` or `SESSION`, in
+/// that order, or `None` if none are set. Loads `.env` first.
+///
+/// # Errors
+/// If the chosen source fails.
+pub fn from_env() -> Result<Option<Session>, SessionError> {
+    dotenv::dotenv().ok();
+    from_vars(|name| std::env::var(name).ok())
+}
+
+fn from_vars(var: impl Fn(&str) -> Option<String>) -> Result<Option<Session>, SessionError> {
+    if let Some(command) = var("AOC_SESSION_CMD") {
+        from_command(&command).map(Some)
+    } else if let Some(path) = var("AOC_SESSION_FILE") {
+        from_file(Path::new(&path)).map(Some)
+    } else {
+        var("SESSION")
+            .map(|token| Session::new(&token, "SESSION"))
+            .transpose()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn temp_file(name: &str, contents: &str, mode: u32) -> PathBuf {
+        let path = std::env::temp_dir().join(format!("libaoc-{name}-{}", std::process::id()));
+        std::fs::write(&path, contents).unwrap();
+        #[cfg(unix)]
+        {
+            use std::os::unix::fs::PermissionsExt;
+            std::fs::set_permissions(&path, std::fs::Permissions::from_mode(mode)).unwrap();
+        }
+        #[cfg(not(unix))]
+        let _ = mode;
+        path
+    }
+
+    #[test]
+    fn formatting_hides_the_token() {
+        let session = Session::new("53616c746564\n", "test").unwrap();
+        assert_eq!(session.expose(), "53616c746564");
+        assert_eq!(format!("{session} {session:?}"), "[redacted] [redacted]");
+        assert_eq!(
+            session.redact("GET /input failed (token 53616c746564)"),
+            "GET /input failed (token [redacted])"
+        );
+        assert_eq!(
+            redact("Cookie: session=abc123; other=1"),
+            "Cookie: session=[redacted]; other=1"
+        );
+        assert!(matches!(
+            Session::new("  \n", "test"),
+            Err(SessionError::Empty(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(unix)]
+    fn files_must_be_private() {
+        let open = temp_file("open-session", "abc\n", 0o644);
+        assert!(matches!(
+            from_file(&open),
+            Err(SessionError::WorldReadable { .. })
+        ));
+        let private = temp_file("private-session", "abc\n", 0o600);
+        assert_eq!(from_file(&private).unwrap().expose(), "abc");
+        std::fs::remove_file(open).unwrap();
+        std::fs::remove_file(private).unwrap();
+    }
+
+    #[test]
+    fn home_is_expanded() {
+        let home = || Some(OsString::from("/home/alex"));
+        let expand = |path: &str| expand_home(Path::new(path), home());
+        assert_eq!(expand("~/.config/aoc"), Path::new("/home/alex/.config/aoc"));
+        assert_eq!(expand("~"), Path::new("/home/alex"));
+        assert_eq!(expand("~sam/token"), Path::new("~sam/token"));
+        assert_eq!(expand("/srv/~/token"), Path::new("/srv/~/token"));
+        assert_eq!(
+            expand_home(Path::new("~/token"), None),
+            Path::new("~/token")
+        );
+    }
+
+    #[test]
+    fn commands() {
+        assert_eq!(from_command("echo abc").unwrap().expose(), "abc");
+        let error = from_command("echo abc; exit 3").unwrap_err().to_string();
+        assert!(error.contains("exit status: 3") && !error.contains("abc\n"));
+        assert!(matches!(from_command("true"), Err(SessionError::Empty(_))));
+    }
+
+    #[test]
+    fn env_precedence() {
+        let private = temp_file("env-session", "from-file", 0o600);
+        let path = private.to_str().unwrap().to_owned();
+        let vars = |cmd: bool, file: bool| {
+            let path = path.clone();
+            move |name: &str| match name {
+                "AOC_SESSION_CMD" if cmd => Some("echo from-cmd".into()),
+                "AOC_SESSION_FILE" if file => Some(path.clone()),
+                "SESSION" => Some("from-env".into()),
+                _ => None,
+            }
+        };
+        let token = |cmd, file| from_vars(vars(cmd, file)).unwrap().unwrap().0;
+        assert_eq!(token(true, true), "from-cmd");
+        assert_eq!(token(false, true), "from-file");
+        assert_eq!(token(false, false), "from-env");
+        assert!(from_vars(|_| None).unwrap().is_none());
+        std::fs::remove_file(private).unwrap();
+    }
+}
@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+use std::hash::Hash;
+
+/// Apply `step` until the state stops changing, returning the stable state and
+/// the number of steps that changed it.
+pub fn fixpoint<S, F>(mut state: S, mut step: F) -> (S, usize)
+where
+    S: PartialEq,
+    F: FnMut(&S) -> S,
+{
+    let mut steps = 0;
+    loop {
+        let next = step(&state);
+        if next == state {
+            return (state, steps);
+        }
+        state = next;
+        steps += 1;
+    }
+}
+
+/// Apply an in-place `step` until it reports that nothing changed, returning
+/// the number of steps that did change the state.
+pub fn fixpoint_mut<S, F>(state: &mut S, mut step: F) -> usize
+where
+    F: FnMut(&mut S) -> bool,
+{
+    let mut steps = 0;
+    while step(state) {
+        steps += 1;
+    }
+    steps
+}
+
+/// The shape of the eventually periodic sequence `x0, f(x0), f(f(x0)), ...`
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct Cycle {
+    /// Index of the first state that is part of the cycle.
+    pub start: usize,
+    /// Number of states in the cycle.
+    pub length: usize,
+}
+
+impl Cycle {
+    /// The smallest step index whose state equals the state at step `n`.
+    #[must_use]
+    pub fn index(&self, n: usize) -> usize {
+        if n < self.start {
+            n
+        } else {
+            self.start + (n - self.start) % self.length
+        }
+    }
+
+    /// The state after `n` steps, found by stepping only `self.index(n)` times.
+    pub fn nth<S, F>(&self, mut state: S, mut step: F, n: usize) -> S
+    where
+        F: FnMut(&S) -> S,
+    {
+        for _ in 0..self.index(n) {
+            state = step(&state);
+        }
+        state
+    }
+}
+
+/// Floyd's tortoise and hare. Uses constant memory but steps roughly three
+/// times per state visited.
+pub fn floyd<S, F>(x0: S, mut step: F) -> Cycle
+where
+    S: Clone + PartialEq,
+    F: FnMut(&S) -> S,
+{
+    let mut tortoise = step(&x0);
+    let mut hare = step(&tortoise);
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        hare = step(&hare);
+    }
+
+    let mut start = 0;
+    tortoise = x0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+
+    let mut length = 1;
+    hare = step(&tortoise);
+    while tortoise != hare {
+        hare = step(&hare);
+        length += 1;
+    }
+    Cycle { start, length }
+}
+
+/// Brent's algorithm. Uses constant memory and fewer steps than [`floyd`].
+pub fn brent<S, F>(x0: S, mut step: F) -> Cycle
+where
+    S: Clone + PartialEq,
+    F: FnMut(&S) -> S,
+{
+    let mut power = 1;
+    let mut length = 1;
+    let mut tortoise = x0.clone();
+    let mut hare = step(&x0);
+    while tortoise != hare {
+        if power == length {
+            tortoise = hare.clone();
+            power *= 2;
+            length = 0;
+        }
+        hare = step(&hare);
+        length += 1;
+    }
+
+    let mut tortoise = x0.clone();
+    let mut hare = x0;
+    for _ in 0..length {
+        hare = step(&hare);
+    }
+    let mut start = 0;
+    while tortoise != hare {
+        tortoise = step(&tortoise);
+        hare = step(&hare);
+        start += 1;
+    }
+    Cycle { start, length }
+}
+
+/// Hash-based cycle detection, stepping each state exactly once and returning
+/// every state seen before the cycle repeats.
+pub fn find_cycle<S, F>(x0: S, mut step: F) -> (Cycle, Vec<S>)
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> S,
+{
+    let mut seen = HashMap::new();
+    let mut history = Vec::new();
+    let mut state = x0;
+    loop {
+        if let Some(&start) = seen.get(&state) {
+            let length = history.len() - start;
+            return (Cycle { start, length }, history);
+        }
+        seen.insert(state.clone(), history.len());
+        let next = step(&state);
+        history.push(state);
+        state = next;
+    }
+}
+
+/// The state after `n` steps from `x0`, stopping early and extrapolating as
+/// soon as a repeated state is found.
+pub fn nth_state<S, F>(x0: S, mut step: F, n: usize) -> S
+where
+    S: Clone + Eq + Hash,
+    F: FnMut(&S) -> S,
+{
+    let mut seen = HashMap::new();
+    let mut history = Vec::new();
+    let mut state = x0;
+    while history.len() < n {
+        if let Some(&start) = seen.get(&state) {
+            let cycle = Cycle {
+                start,
+                length: history.len() - start,
+            };
+            return history.swap_remove(cycle.index(n));
+        }
+        seen.insert(state.clone(), history.len());
+        let next = step(&state);
+        history.push(state);
+        state = next;
+    }
+    state
+}
+
+/// A memoized recursive function. The wrapped function receives a `recurse`
+/// callback to use in place of calling itself directly.
+///
+/// ```
+/// # use libaoc::sim::Memo;
+/// let mut fib = Memo::new(|recurse: &mut dyn FnMut(u64) -> u64, n: u64| {
+///     if n < 2 { n } else { recurse(n - 1) + recurse(n - 2) }
+/// });
+/// assert_eq!(fib.get(90), 2_880_067_194_370_816_120);
+/// ```
+pub struct Memo<K, V, F> {
+    cache: HashMap<K, V>,
+    f: F,
+}
+
+impl<K, V, F> Memo<K, V, F>
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    pub fn new(f: F) -> Self {
+        Self {
+            cache: HashMap::new(),
+            f,
+        }
+    }
+
+    /// Evaluate at `key`, reusing results cached by earlier calls.
+    pub fn get(&mut self, key: K) -> V {
+        memoized(&mut self.cache, &self.f, key)
+    }
+
+    /// Number of cached results.
+    #[must_use]
+    pub fn len(&self) -> usize {
+        self.cache.len()
+    }
+
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.cache.is_empty()
+    }
+}
+
+fn memoized<K, V, F>(cache: &mut HashMap<K, V>, f: &F, key: K) -> V
+where
+    K: Clone + Eq + Hash,
+    V: Clone,
+    F: Fn(&mut dyn FnMut(K) -> V, K) -> V,
+{
+    if let Some(v) = cache.get(&key) {
+        return v.clone();
+    }
+    let value = f(&mut |k| memoized(cache, f, k), key.clone());
+    cache.insert(key, value.clone());
+    value
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// A sequence with a tail of length 3 before entering a cycle of length 5.
+    fn step(x: &u32) -> u32 {
+        if *x == 7 { 3 } else { x + 1 }
+    }
+
+    #[test]
+    fn fixpoints() {
+        assert_eq!(fixpoint(1000u32, |x| x / 2), (0, 10));
+        let mut v = vec![5, 3, 8, 1];
+        let swaps = fixpoint_mut(&mut v, |v| {
+            let i = v.windows(2).position(|w| w[0] > w[1]);
+            i.inspect(|&i| v.swap(i, i + 1)).is_some()
+        });
+        assert_eq!(v, [1, 3, 5, 8]);
+        assert_eq!(swaps, 4);
+    }
+
+    #[test]
+    fn detectors_agree() {
+        let expected = Cycle {
+            start: 3,
+            length: 5,
+        };
+        assert_eq!(floyd(0, step), expected);
+        assert_eq!(brent(0, step), expected);
+        let (cycle, history) = find_cycle(0, step);
+        assert_eq!(cycle, expected);
+        assert_eq!(history, [0, 1, 2, 3, 4, 5, 6, 7]);
+    }
+
+    #[test]
+    fn pure_cycle() {
+        let rotate = |x: &u8| (x + 1) % 4;
+        let expected = Cycle {
+            start: 0,
+            length: 4,
+        };
+        assert_eq!(floyd(0, rotate), expected);
+        assert_eq!(brent(0, rotate), expected);
+        assert_eq!(find_cycle(0, rotate).0, expected);
+    }
+
+    #[test]
+    fn extrapolation_matches_simulation() {
+        let cycle = brent(0, step);
+        for n in 0..50 {
+            let mut x = 0;
+            for _ in 0..n {
+                x = step(&x);
+            }
+            assert_eq!(nth_state(0, step, n), x, "n = {n}");
+            assert_eq!(cycle.nth(0, step, n), x, "n = {n}");
+        }
+        assert_eq!(
+            nth_state(0, step, 1_000_000_000),
+            3 + (1_000_000_000 - 3) % 5
+        );
+    }
+
+    #[test]
+    fn memo_counts_paths() {
+        // Lattice paths through an n x n grid, as a two-argument recursion.
+        let mut paths = Memo::new(
+            |recurse: &mut dyn FnMut((u64, u64)) -> u64, (r, c): (u64, u64)| {
+                if r == 0 || c == 0 {
+                    1
+                } else {
+                    recurse((r - 1, c)) + recurse((r, c - 1))
+                }
+            },
+        );
+        assert_eq!(paths.get((16, 16)), 601_080_390);
+        assert_eq!(paths.len(), 17 * 17 - 1);
+    }
+}
@@ -0,0 +1,92 @@
+//! Stars earned so far, from the personal stats page (`/{year}/leaderboard/self`).
+use std::collections::BTreeMap;
+
+use crate::http::{self, Client};
+use crate::session::Session;
+use crate::{AocError, BASE_URI, auth};
+
+/// Which parts of each day `session`'s account has solved. Days without a
+/// star are left out.
+///
+/// # Errors
+/// `AocError::SessionExpired` if the page is the logged-out one, else
+/// `AocError::Network`.
+pub fn stars(year: u16, session: &Session) -> Result<BTreeMap<u8, [bool; 2]>, AocError> {
+    stars_at(http::client(), BASE_URI, year, session)
+}
+
+fn stars_at(
+    client: &Client,
+    base: &str,
+    year: u16,
+    session: &Session,
+) -> Result<BTreeMap<u8, [bool; 2]>, AocError> {
+    let uri = format!("{base}/{year}/leaderboard/self");
+    let (code, page) = client.get(&uri, session)?;
+    if !(200..300).contains(&code) {
+        return Err(AocError::Network(format!("{uri} returned {code}")));
+    }
+    if auth::user_name(&page).is_none() {
+        return Err(AocError::SessionExpired);
+    }
+    Ok(parse(&page))
+}
+
+/// Read the table of times, one line per started day, e.g.
+/// ` 4   00:10:12   1234      0          -      -      -`. Each part has
+/// the same number of columns, and unsolved parts show `-`.
+fn parse(page: &str) -> BTreeMap<u8, [bool; 2]> {
+    page.lines()
+        .filter_map(|line| {
+            let mut fields = line.split_whitespace();
+            let day = fields.next()?.parse().ok()?;
+            let fields: Vec<_> = fields.collect();
+            let solved = |i: usize| fields.get(i).is_some_and(|&f| f != "-");
+            Some((day, [solved(0), solved(fields.len() / 2)]))
+        })
+        .filter(|&(_, stars)| stars[0])
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use std::time::Duration;
+
+    use super::*;
+    use crate::mock;
+
+    const PAGE: &str = r#"<header><div class="user">alex <span class="star-count">3*</span></div></header>
+<main><article><pre>      <span class="leaderboard-daydesc-first">-------Part 1--------</span>   <span class="leaderboard-daydesc-both">-------Part 2--------</span>
+Day       <span class="leaderboard-daydesc-first">Time   Rank  Score</span>       <span class="leaderboard-daydesc-both">Time   Rank  Score</span>
+  4   00:10:12   1234      0          -      -      -
+  2   01:02:03   5000      0   01:20:00   4000      0
+  1       &gt;24h  20000      0       &gt;24h  19000      0
+</pre></article></main>"#;
+
+    #[test]
+    fn parses_the_table() {
+        let stars = parse(PAGE);
+        assert_eq!(
+            stars.into_iter().collect::<Vec<_>>(),
+            [(1, [true, true]), (2, [true, true]), (4, [true, false])]
+        );
+        // Years without a global leaderboard only list times.
+        assert_eq!(parse(" 3  00:05:00        -\n")[&3], [true, false]);
+    }
+
+    #[test]
+    fn fetches_the_page() {
+        let client = Client::new("libaoc-tests")
+            .lock_file(std::env::temp_dir().join(format!("libaoc-stats-{}", std::process::id())))
+            .min_interval(Duration::ZERO)
+            .retries(0, Duration::ZERO);
+        let session = Session::new("abc", "test").unwrap();
+        let (base, server) = mock::serve(vec![(200, PAGE), (200, "<a>[Log In]</a>")]);
+        assert_eq!(stars_at(&client, &base, 2025, &session).unwrap().len(), 3);
+        assert!(matches!(
+            stars_at(&client, &base, 2025, &session),
+            Err(AocError::SessionExpired)
+        ));
+        assert!(server.join().unwrap()[0].starts_with("GET /2025/leaderboard/self "));
+    }
+}
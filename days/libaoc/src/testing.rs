@@ -0,0 +1,222 @@
+//! Proptest strategies for puzzle-shaped inputs, a helper for checking a
+//! fast solver against a slow reference, and the checks and benchmarks every
+//! day's generator gets. Enabled by the `testing` feature.
+use std::fmt::Debug;
+use std::ops::RangeInclusive;
+
+use criterion::{BenchmarkId, Criterion};
+use proptest::collection::{SizeRange, vec};
+use proptest::prelude::*;
+use proptest::test_runner::TestRunner;
+
+use crate::grammar::Grammar;
+use crate::registry::{Generator, Solver};
+
+/// Strings of `len` digits drawn from `digits`, e.g. `b'1'..=b'9'` for
+/// battery banks. A leading zero is never produced.
+pub fn digit_string(
+    len: impl Into<SizeRange>,
+    digits: RangeInclusive<u8>,
+) -> impl Strategy<Value = String> {
+    assert!(digits.end().is_ascii_digit() && digits.start().is_ascii_digit());
+    let first = (*digits.start()).max(b'1')..=*digits.end();
+    (first, vec(digits, len)).prop_map(|(first, mut rest)| {
+        if let Some(d) = rest.first_mut() {
+            *d = first;
+        }
+        String::from_utf8(rest).expect("digits are ASCII")
+    })
+}
+
+/// Lists of inclusive `(low, high)` ranges with `low <= high <= max`.
+pub fn ranges(
+    count: impl Into<SizeRange>,
+    max: usize,
+) -> impl Strategy<Value = Vec<(usize, usize)>> {
+    vec((0..=max, 0..=max), count).prop_map(|pairs| {
+        pairs
+            .into_iter()
+            .map(|(a, b)| (a.min(b), a.max(b)))
+            .collect()
+    })
+}
+
+/// Newline-separated grids of `rows` x `columns` cells drawn from `alphabet`.
+pub fn grid(
+    rows: impl Strategy<Value = usize>,
+    columns: impl Strategy<Value = usize>,
+    alphabet: &'static [u8],
+) -> impl Strategy<Value = String> {
+    (rows, columns).prop_flat_map(move |(rows, columns)| {
+        let cell = proptest::sample::select(alphabet);
+        vec(vec(cell, columns), rows).prop_map(|lines| {
+            lines
+                .into_iter()
+                .map(|line| String::from_utf8(line).expect("alphabet should be ASCII"))
+                .collect::<Vec<_>>()
+                .join("\n")
+        })
+    })
+}
+
+/// Lists of signed rotations with magnitude in `1..=max`; left is negative.
+pub fn rotations(count: impl Into<SizeRange>, max: i32) -> impl Strategy<Value = Vec<i32>> {
+    vec((any::<bool>(), 1..=max), count).prop_map(|turns| {
+        turns
+            .into_iter()
+            .map(|(left, n)| if left { -n } else { n })
+            .collect()
+    })
+}
+
+/// Render rotations as puzzle lines: `L68`, `R48`, ...
+#[must_use]
+pub fn rotation_lines(rotations: &[i32]) -> String {
+    rotations
+        .iter()
+        .map(|&r| format!("{}{}\n", if r < 0 { 'L' } else { 'R' }, r.unsigned_abs()))
+        .collect()
+}
+
+/// Check that `fast` and `reference` agree on every input drawn from
+/// `strategy`, shrinking to a minimal counterexample on failure.
+///
+/// # Panics
+/// If the solvers disagree on any input.
+pub fn assert_equivalent<S, O, F, R>(strategy: S, fast: F, reference: R)
+where
+    S: Strategy,
+    S::Value: Debug,
+    O: PartialEq + Debug,
+    F: Fn(&S::Value) -> O,
+    R: Fn(&S::Value) -> O,
+{
+    let mut runner = TestRunner::default();
+    let result = runner.run(&strategy, |input| {
+        prop_assert_eq!(fast(&input), reference(&input), "input: {:?}", input);
+        Ok(())
+    });
+    if let Err(e) = result {
+        panic!("{e}");
+    }
+}
+
+/// Check that each of `solvers` returns rather than panics on arbitrary
+/// bytes, read as lossy UTF-8.
+///
+/// # Panics
+/// If a solver panics on any input.
+pub fn assert_no_panic(solvers: &[Solver]) {
+    let mut runner = TestRunner::default();
+    let result = runner.run(&any::<Vec<u8>>(), |input| {
+        let input = String::from_utf8_lossy(&input);
+        for solver in solvers {
+            let _ = solver(&input);
+        }
+        Ok(())
+    });
+    if let Err(e) = result {
+        panic!("{e}");
+    }
+}
+
+/// A generated input of `size`, after checking that the generator is
+/// deterministic and its output matches `grammar`. Day-specific checks can
+/// then be made on the returned input.
+///
+/// # Panics
+/// If two inputs from the same seed differ, or the input does not match.
+pub fn generated_input(generate: Generator, grammar: &Grammar, size: usize) -> String {
+    let input = generate(7, size);
+    assert_eq!(input, generate(7, size), "same seed, different input");
+    assert_eq!(grammar.validate(&input), Ok(()));
+    input
+}
+
+/// Benchmark both parts on generated inputs of each of `sizes`, to see how
+/// they scale.
+pub fn scaling(
+    c: &mut Criterion,
+    generate: Generator,
+    sizes: &[usize],
+    parts: [fn(&str) -> usize; 2],
+) {
+    let mut group = c.benchmark_group("scaling");
+    for &size in sizes {
+        let input = generate(0, size);
+        for (name, part) in ["part_1", "part_2"].into_iter().zip(parts) {
+            group.bench_with_input(BenchmarkId::new(name, size), &input, |b, input| {
+                b.iter(|| part(input));
+            });
+        }
+    }
+    group.finish();
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn digit_strings(s in digit_string(1..20, b'0'..=b'9')) {
+            prop_assert!(!s.starts_with('0'));
+            prop_assert!(s.bytes().all(|b| b.is_ascii_digit()));
+        }
+
+        #[test]
+        fn ordered_ranges(r in ranges(0..10, 1000)) {
+            prop_assert!(r.iter().all(|&(a, b)| a <= b && b <= 1000));
+        }
+
+        #[test]
+        fn rectangular_grids(g in grid(1..5usize, 1..5usize, b".@")) {
+            let widths: Vec<_> = g.lines().map(str::len).collect();
+            prop_assert!(widths.windows(2).all(|w| w[0] == w[1]));
+            prop_assert!(g.bytes().all(|b| b"\n.@".contains(&b)));
+        }
+
+        #[test]
+        fn bounded_rotations(r in rotations(0..10, 5)) {
+            prop_assert!(r.iter().all(|n| (1..=5).contains(&n.abs())));
+        }
+    }
+
+    #[test]
+    fn renders_rotations() {
+        assert_eq!(rotation_lines(&[-68, 48]), "L68\nR48\n");
+    }
+
+    #[test]
+    fn equivalent_solvers_pass() {
+        assert_equivalent(0..100u32, |&n| n / 2, |&n| n >> 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "unparsable")]
+    fn panicking_solvers_panic() {
+        assert_no_panic(&[|_| Ok(0), |_| panic!("unparsable")]);
+    }
+
+    #[test]
+    fn checks_generators() {
+        let grammar = Grammar::lines(r"\d+");
+        let input = generated_input(|seed, size| format!("{seed}\n").repeat(size), &grammar, 2);
+        assert_eq!(input, "7\n7\n");
+    }
+
+    #[test]
+    #[should_panic(expected = "same seed")]
+    fn nondeterministic_generators_panic() {
+        use std::sync::atomic::{AtomicUsize, Ordering};
+        static CALLS: AtomicUsize = AtomicUsize::new(0);
+        let generate = |_, _| format!("{}\n", CALLS.fetch_add(1, Ordering::Relaxed));
+        generated_input(generate, &Grammar::lines(r"\d+"), 1);
+    }
+
+    #[test]
+    #[should_panic(expected = "input")]
+    fn disagreement_panics() {
+        assert_equivalent(0..100u32, |&n| n / 2, |&n| if n >= 50 { 0 } else { n / 2 });
+    }
+}
@@ -0,0 +1,11 @@
+#![no_main]
+
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    if let Ok(input) = std::str::from_utf8(data) {
+        let _ = day05::try_part_1(input);
+        let _ = day05::try_part_1_sweep(input);
+        let _ = day05::try_part_2(input);
+    }
+});
@@ -1,7 +1,33 @@
-pub fn part_1(_input: &str) -> usize {
+use libaoc::InputError;
+use libaoc::aoc;
+use libaoc::grammar::Grammar;
+
+/// The expected input format, checked before solving once passed to
+/// `aoc_main!` in `main.rs`.
+#[aoc(grammar)]
+pub fn grammar() -> Grammar {
+    todo!()
+}
+
+#[aoc(part = 1)]
+pub fn try_part_1(_input: &str) -> Result<usize, InputError> {
     todo!()
 }
 
-pub fn part_2(_input: &str) -> usize {
+#[aoc(part = 2)]
+pub fn try_part_2(_input: &str) -> Result<usize, InputError> {
+    todo!()
+}
+
+pub fn part_1(input: &str) -> usize {
+    try_part_1(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn part_2(input: &str) -> usize {
+    try_part_2(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+#[aoc(generator)]
+pub fn generate(_seed: u64, _size: usize) -> String {
     todo!()
 }
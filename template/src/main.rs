@@ -1,7 +1,5 @@
 use day0_::{part_1, part_2};
 
-pub fn main() {
-    let year = 2025;
-    let day = _;
-    libaoc::evaluate(part_1, part_2, year, day);
-}
+// Once `grammar` is written, pass it as a third argument to check the input
+// before solving.
+libaoc::aoc_main!(part_1, part_2);
@@ -0,0 +1,109 @@
+use libaoc::InputError;
+use libaoc::aoc;
+use libaoc::grammar::Grammar;
+use libaoc::rng::Rng;
+
+/// The left and right location id lists, one pair per line.
+pub fn lists(input: &str) -> Result<(Vec<usize>, Vec<usize>), InputError> {
+    input
+        .lines()
+        .enumerate()
+        .map(|(i, line)| {
+            let mut ids = line.split_whitespace().map(str::parse::<usize>);
+            match (ids.next(), ids.next(), ids.next()) {
+                (Some(Ok(left)), Some(Ok(right)), None) => Ok((left, right)),
+                _ => Err(InputError::malformed(
+                    i + 1,
+                    format!("expected two ids, found {line:?}"),
+                )),
+            }
+        })
+        .collect()
+}
+
+/// Two ids per line, separated by spaces, e.g. `3   4`.
+#[aoc(grammar)]
+pub fn grammar() -> Grammar {
+    Grammar::lines(r"\d+ +\d+")
+}
+
+#[aoc(part = 1)]
+pub fn try_part_1(input: &str) -> Result<usize, InputError> {
+    let (mut left, mut right) = lists(input)?;
+    left.sort_unstable();
+    right.sort_unstable();
+    Ok(left.iter().zip(&right).map(|(l, r)| l.abs_diff(*r)).sum())
+}
+
+#[aoc(part = 2)]
+pub fn try_part_2(input: &str) -> Result<usize, InputError> {
+    let (left, right) = lists(input)?;
+    left.iter()
+        .map(|l| l.checked_mul(right.iter().filter(|r| *r == l).count()))
+        .try_fold(0usize, |total, score| total.checked_add(score?))
+        .ok_or_else(|| InputError::Unsolvable("similarity score overflows".into()))
+}
+
+pub fn part_1(input: &str) -> usize {
+    try_part_1(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+pub fn part_2(input: &str) -> usize {
+    try_part_2(input).unwrap_or_else(|e| panic!("{e}"))
+}
+
+/// A seeded puzzle input of `size` pairs of five digit ids, drawn from a
+/// shared pool so that ids repeat across the lists as in real inputs.
+#[aoc(generator)]
+pub fn generate(seed: u64, size: usize) -> String {
+    let mut rng = Rng::new(seed);
+    let pool: Vec<_> = (0..size / 4 + 1)
+        .map(|_| rng.range(10_000..100_000))
+        .collect();
+    (0..size)
+        .map(|_| format!("{}   {}\n", rng.pick(&pool), rng.pick(&pool)))
+        .collect()
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+
+    const EXAMPLE: &str = "3   4\n4   3\n2   5\n1   3\n3   9\n3   3\n";
+
+    #[test]
+    fn example() {
+        assert_eq!(part_1(EXAMPLE), 11);
+        assert_eq!(part_2(EXAMPLE), 31);
+    }
+
+    #[test]
+    fn invalid_lines_are_errors() {
+        for line in ["", "3", "3 4 5", "3 x", "-3 4"] {
+            assert_eq!(
+                try_part_1(&format!("1 2\n{line}\n")),
+                Err(InputError::malformed(
+                    2,
+                    format!("expected two ids, found {line:?}")
+                ))
+            );
+        }
+    }
+
+    #[test]
+    fn generated_input() {
+        let input = libaoc::testing::generated_input(generate, &grammar(), 200);
+        let (left, right) = lists(&input).unwrap();
+        let similarity: usize = left
+            .iter()
+            .flat_map(|l| right.iter().filter(move |r| *r == l))
+            .sum();
+        assert_eq!(part_2(&input), similarity);
+        assert_ne!(similarity, 0);
+    }
+
+    #[test]
+    fn arbitrary_input_does_not_panic() {
+        libaoc::testing::assert_no_panic(&[try_part_1, try_part_2]);
+    }
+}
@@ -0,0 +1,3 @@
+use aoc2024_day01::{grammar, part_1, part_2};
+
+libaoc::aoc_main!(part_1, part_2, grammar);